@@ -0,0 +1,104 @@
+use advection_simulation::{Descriptor, Scenario, SpatialScheme, TemporalScheme};
+use strum::IntoEnumIterator;
+
+const CELLS: usize = 1000;
+const WARMUP_STEPS: usize = 10;
+const STEPS: usize = 200;
+const CFL: f64 = 0.4;
+const MAX_GROWTH: f64 = 1.5;
+const SWEEP_CELLS: [usize; 4] = [1000, 2500, 5000, 10000];
+
+fn main() {
+    schemes();
+    sweep();
+    spawn();
+}
+
+fn schemes() {
+    for spatial_scheme in SpatialScheme::iter() {
+        for temporal_scheme in TemporalScheme::iter() {
+            let mut desc = Descriptor::new();
+            desc.spatial_scheme = spatial_scheme;
+            desc.temporal_scheme = temporal_scheme;
+            desc.delta_x = desc.bound / CELLS as f64;
+            desc.delta_t = CFL * desc.delta_x / desc.vel.abs();
+
+            let Ok(mut scenario) = Scenario::new(desc) else {
+                continue;
+            };
+
+            for _ in 0..WARMUP_STEPS {
+                scenario.forward();
+            }
+
+            let instant = std::time::Instant::now();
+            for _ in 0..STEPS {
+                scenario.forward();
+            }
+            let rate = STEPS as f64 / instant.elapsed().as_secs_f64();
+
+            let name = format!(
+                "{:<14} {:<15}",
+                format!("{:?}", spatial_scheme),
+                format!("{:?}", temporal_scheme)
+            );
+            // Non-finite arithmetic runs at a different speed, so the rate of
+            // a run that blew up says nothing about the scheme.
+            match scenario.diverged {
+                Some(t) => println!("{} {:>12} at t = {:.3}", name, "diverged", t),
+                None if MAX_GROWTH < scenario.peak_ratio() => {
+                    println!(
+                        "{} {:>12} peak x{:.2e}",
+                        name,
+                        "unstable",
+                        scenario.peak_ratio()
+                    )
+                }
+                None => println!("{} {:>12.0} steps/s", name, rate),
+            }
+        }
+    }
+}
+
+// The cost per cell and stage as the grid grows, which is what sets the
+// threshold for splitting cells across threads with the parallel feature.
+fn sweep() {
+    println!();
+    for cells in SWEEP_CELLS {
+        let mut desc = Descriptor::new();
+        desc.spatial_scheme = SpatialScheme::WENO;
+        desc.temporal_scheme = TemporalScheme::TvdRk3;
+        desc.delta_x = desc.bound / cells as f64;
+        desc.delta_t = CFL * desc.delta_x / desc.vel.abs();
+        let mut scenario = Scenario::new(desc).unwrap();
+
+        let instant = std::time::Instant::now();
+        for _ in 0..STEPS {
+            scenario.forward();
+        }
+        let elapsed = instant.elapsed().as_secs_f64();
+        let stages = STEPS * scenario.desc.stages() * cells;
+
+        println!(
+            "WENO TvdRk3 {:>6} cells {:>10.0} steps/s {:>8.1} ns/cell/stage",
+            cells,
+            STEPS as f64 / elapsed,
+            elapsed / stages as f64 * 1e9
+        );
+    }
+}
+
+fn spawn() {
+    let instant = std::time::Instant::now();
+    for _ in 0..STEPS {
+        std::thread::scope(|scope| {
+            scope.spawn(|| std::hint::black_box(0));
+        });
+    }
+    let elapsed = instant.elapsed().as_secs_f64();
+
+    println!(
+        "scoped thread spawn and join {:>8.1} us",
+        elapsed / STEPS as f64 * 1e6
+    );
+}
@@ -1,19 +1,89 @@
 use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use crate::simulation::{Descriptor, Scenario};
+use advection_simulation::simulation::{self, ConvergenceRow, Dimension};
+use advection_simulation::{config, Descriptor, Scenario, SpatialScheme, TemporalScheme};
+
+const COLORS: [egui::Color32; 6] = [
+    egui::Color32::RED,
+    egui::Color32::LIGHT_BLUE,
+    egui::Color32::YELLOW,
+    egui::Color32::from_rgb(255, 0, 255),
+    egui::Color32::from_rgb(255, 128, 0),
+    egui::Color32::WHITE,
+];
+
+const CONVERGENCE_LEVELS: usize = 4;
+
+type ConvergenceResult = Result<Vec<ConvergenceRow>, String>;
+
+#[derive(Clone, Debug)]
+struct ConvergenceJob {
+    progress: Arc<simulation::Progress>,
+    result: Arc<Mutex<Option<ConvergenceResult>>>,
+    started: std::time::Instant,
+}
 
 #[derive(Clone, Debug)]
 pub struct Body {
     desc: Descriptor,
-    scenario: Arc<Mutex<Option<Scenario>>>,
+    scenarios: Arc<Mutex<Vec<Scenario>>>,
+    dropped: Option<Vec<Scenario>>,
+    paused: Arc<AtomicBool>,
+    pending_steps: Arc<AtomicUsize>,
+    step_rate: Arc<AtomicU64>,
+    substeps: Arc<AtomicUsize>,
+    frame_driven: Arc<AtomicBool>,
+    fixed_steps: bool,
+    steps_per_frame: usize,
+    last_update: Option<std::time::Instant>,
+    config_path: String,
+    convergence: Option<Vec<ConvergenceRow>>,
+    convergence_job: Option<ConvergenceJob>,
+    frame: Option<usize>,
+    show_space_time: bool,
+    show_dispersion: bool,
+    show_peak: bool,
+    show_energy: bool,
+    comparison_scheme: SpatialScheme,
+    status: Option<Result<String, String>>,
+    screenshot: Option<String>,
+    plot_rect: Option<egui::Rect>,
+    fit_plot: bool,
+    confirm_clear: bool,
+    applied_bounds: Option<[f64; 4]>,
 }
 
 impl Body {
-    pub fn new() -> Self {
+    pub fn new(desc: Descriptor) -> Self {
         Self {
-            desc: Descriptor::new(),
-            scenario: Arc::new(Mutex::new(None)),
+            desc,
+            scenarios: Arc::new(Mutex::new(vec![])),
+            dropped: None,
+            paused: Arc::new(AtomicBool::new(false)),
+            pending_steps: Arc::new(AtomicUsize::new(0)),
+            step_rate: Arc::new(AtomicU64::new(0.0_f64.to_bits())),
+            substeps: Arc::new(AtomicUsize::new(1)),
+            frame_driven: Arc::new(AtomicBool::new(false)),
+            fixed_steps: false,
+            steps_per_frame: 1,
+            last_update: None,
+            config_path: "config.json".to_string(),
+            convergence: None,
+            convergence_job: None,
+            frame: None,
+            show_space_time: false,
+            show_dispersion: false,
+            show_peak: false,
+            show_energy: false,
+            comparison_scheme: SpatialScheme::Upwind,
+            status: None,
+            screenshot: None,
+            plot_rect: None,
+            fit_plot: false,
+            confirm_clear: false,
+            applied_bounds: None,
         }
     }
 
@@ -23,13 +93,55 @@ impl Body {
             .show(ctx, |ui| {
                 ui.heading("Parameters");
                 self.desc.show_inside(ui);
+
+                ui.separator();
+
+                ui.text_edit_singleline(&mut self.config_path);
+                ui.horizontal(|ui| {
+                    if ui.button("Save Config").clicked() {
+                        self.status = Some(
+                            config::save(&self.config_path, &self.desc)
+                                .map(|_| format!("Saved {}", self.config_path)),
+                        );
+                    }
+
+                    if ui.button("Load Config").clicked() {
+                        self.status = Some(config::load(&self.config_path).map(|desc| {
+                            self.desc = desc;
+                            format!("Loaded {}", self.config_path)
+                        }));
+                    }
+                });
+
+                match &self.status {
+                    Some(Ok(message)) => {
+                        ui.label(egui::RichText::new(message).color(egui::Color32::GREEN));
+                    }
+                    Some(Err(message)) => {
+                        ui.label(egui::RichText::new(message).color(egui::Color32::RED));
+                    }
+                    None => {}
+                }
             });
 
+        if self.show_space_time {
+            egui::TopBottomPanel::bottom("space_time")
+                .resizable(true)
+                .default_height(200.0)
+                .show(ctx, |ui| {
+                    egui_plot::Plot::new("Space-Time").show(ui, |ui| {
+                        if let Some(scenario) = self.scenarios.lock().first() {
+                            scenario.show_space_time(ui);
+                        }
+                    });
+                });
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.heading("Simulation");
 
-                let text = if self.scenario.lock().is_some() {
+                let text = if !self.scenarios.lock().is_empty() {
                     egui::RichText::new("Active").color(egui::Color32::GREEN)
                 } else {
                     egui::RichText::new("Inactive").color(egui::Color32::RED)
@@ -37,45 +149,855 @@ impl Body {
                 ui.label(text);
 
                 if ui.button("New Scenario").clicked() {
-                    let scenario = Scenario::new(self.desc.clone());
-                    self.scenario.lock().replace(scenario);
+                    match Scenario::new(self.desc.clone()) {
+                        Ok(scenario) => {
+                            *self.scenarios.lock() = vec![scenario];
+                        }
+                        Err(message) => self.status = Some(Err(message)),
+                    }
+                }
+
+                if ui.button("Apply Parameters").clicked() {
+                    let mut scenarios = self.scenarios.lock();
+                    if let Some(primary) = scenarios.first() {
+                        let primary_temporal_scheme = primary.desc.temporal_scheme;
+                        let result =
+                            scenarios
+                                .iter_mut()
+                                .enumerate()
+                                .try_for_each(|(i, scenario)| {
+                                    let mut desc = self.desc.clone();
+                                    if 0 < i {
+                                        desc.spatial_scheme = scenario.desc.spatial_scheme;
+                                        if scenario.desc.temporal_scheme != primary_temporal_scheme
+                                        {
+                                            desc.temporal_scheme = scenario.desc.temporal_scheme;
+                                        }
+                                    }
+                                    scenario.apply(desc)
+                                });
+                        self.status = Some(result.map(|_| "Applied parameters".to_string()));
+                    }
+                }
+
+                if ui.button("Apply Grid").clicked() {
+                    let mut scenarios = self.scenarios.lock();
+                    if !scenarios.is_empty() {
+                        let delta_x = self.desc.delta_x;
+                        let result = scenarios
+                            .iter_mut()
+                            .try_for_each(|scenario| scenario.regrid(delta_x));
+                        self.status =
+                            Some(result.map(|_| format!("Regridded to dx = {}", delta_x)));
+                    }
+                }
+
+                if ui.button("Reset").clicked() {
+                    self.reset();
                 }
 
                 if ui.button("Drop Scenario").clicked() {
-                    self.scenario.lock().take();
+                    self.drop_scenarios();
+                }
+
+                if ui
+                    .add_enabled(self.dropped.is_some(), egui::Button::new("Restore"))
+                    .clicked()
+                {
+                    self.restore_scenarios();
+                }
+
+                let paused = self.paused.load(Ordering::Relaxed);
+                let text = if paused { "Resume" } else { "Pause" };
+                if ui.button(text).clicked() {
+                    self.toggle_pause();
+                }
+
+                if ui.add_enabled(paused, egui::Button::new("Step")).clicked() {
+                    self.step();
+                }
+
+                let mut reversed = self
+                    .scenarios
+                    .lock()
+                    .first()
+                    .is_some_and(|scenario| scenario.reversed());
+                if ui
+                    .checkbox(&mut reversed, "Reverse")
+                    .on_hover_text("Negate the velocity and run back toward t = 0")
+                    .changed()
+                {
+                    let result = self
+                        .scenarios
+                        .lock()
+                        .iter_mut()
+                        .try_for_each(|scenario| scenario.set_reversed(reversed));
+                    if let Err(message) = result {
+                        self.status = Some(Err(message));
+                    }
+                }
+
+                ui.checkbox(&mut self.fixed_steps, "Fixed Steps per Frame")
+                    .on_hover_text("Advance a fixed number of steps per rendered frame");
+                if self.fixed_steps {
+                    ui.add(
+                        egui::Slider::new(&mut self.steps_per_frame, 1..=100)
+                            .text("Steps per Frame"),
+                    );
+                } else {
+                    let mut substeps = self.substeps.load(Ordering::Relaxed);
+                    ui.add(egui::Slider::new(&mut substeps, 1..=100).text("Substeps"))
+                        .on_hover_text("Steps the worker takes per lock of the scenarios");
+                    self.substeps.store(substeps, Ordering::Relaxed);
+                }
+
+                let display = format!("{:?}", self.comparison_scheme);
+                egui::ComboBox::from_id_source("comparison_scheme")
+                    .selected_text(display)
+                    .show_ui(ui, |ui| {
+                        <SpatialScheme as strum::IntoEnumIterator>::iter().for_each(|scheme| {
+                            let display = format!("{:?}", scheme);
+                            ui.selectable_value(&mut self.comparison_scheme, scheme, display)
+                                .on_hover_text(scheme.description());
+                        });
+                    });
+
+                if ui.button("Add Comparison").clicked() {
+                    self.status = Some(
+                        self.add_comparison()
+                            .map(|_| format!("Added {:?}", self.comparison_scheme)),
+                    );
+                }
+
+                if ui.button("Compare Temporal Schemes").clicked() {
+                    self.status = Some(self.compare_temporal_schemes());
+                }
+
+                let comparisons = self.scenarios.lock().len().saturating_sub(1);
+                if self.confirm_clear && comparisons != 0 {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Clear {} comparisons?", comparisons));
+                        if ui.button("Clear").clicked() {
+                            self.scenarios.lock().truncate(1);
+                            self.confirm_clear = false;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.confirm_clear = false;
+                        }
+                    });
+                } else if ui
+                    .add_enabled(comparisons != 0, egui::Button::new("Clear Comparisons"))
+                    .clicked()
+                {
+                    self.confirm_clear = true;
+                }
+
+                if ui.button("Export CSV").clicked() {
+                    if let Some(scenario) = self.scenarios.lock().first() {
+                        let path = format!("{}.csv", scenario.file_stem());
+                        self.status = Some(
+                            scenario
+                                .export_csv(&path)
+                                .map(|_| format!("Exported {}", path))
+                                .map_err(|e| e.to_string()),
+                        );
+                    }
+                }
+
+                if ui.button("Export Diagnostics").clicked() {
+                    if let Some(scenario) = self.scenarios.lock().first() {
+                        let path = format!("{}_diagnostics.json", scenario.file_stem());
+                        self.status = Some(
+                            scenario
+                                .export_diagnostics(&path)
+                                .map(|_| {
+                                    format!(
+                                        "Exported {} samples to {}",
+                                        scenario.diagnostics.len(),
+                                        path
+                                    )
+                                })
+                                .map_err(|e| e.to_string()),
+                        );
+                    }
+                }
+
+                if ui.button("Clear Diagnostics").clicked() {
+                    for scenario in self.scenarios.lock().iter_mut() {
+                        scenario.diagnostics.clear();
+                    }
+                }
+
+                if ui.button("Export PNG").clicked() {
+                    if let Some(scenario) = self.scenarios.lock().first() {
+                        self.screenshot = Some(format!("{}.png", scenario.file_stem()));
+                    }
+                }
+
+                ui.checkbox(&mut self.show_space_time, "Space-Time");
+                ui.checkbox(&mut self.show_dispersion, "Modified Wavenumber");
+                ui.checkbox(&mut self.show_peak, "Peak Ratio");
+                ui.checkbox(&mut self.show_energy, "Energy");
+
+                let running = self.convergence_job.is_some();
+                if ui
+                    .add_enabled(!running, egui::Button::new("Run Convergence Study"))
+                    .clicked()
+                {
+                    self.run_convergence_study();
+                }
+
+                if let Some(job) = &self.convergence_job {
+                    let level = (job.progress.level() + 1).min(CONVERGENCE_LEVELS);
+                    let text = format!(
+                        "Level {}/{} | {:.1} s",
+                        level,
+                        CONVERGENCE_LEVELS,
+                        job.started.elapsed().as_secs_f64()
+                    );
+                    ui.add(egui::ProgressBar::new(job.progress.fraction() as f32).text(text));
+                    ctx.request_repaint();
+                }
+
+                let finished_job = self
+                    .convergence_job
+                    .as_ref()
+                    .and_then(|job| job.result.lock().take());
+                if let Some(result) = finished_job {
+                    self.convergence_job = None;
+                    match result {
+                        Ok(rows) => self.convergence = Some(rows),
+                        Err(message) => self.status = Some(Err(message)),
+                    }
+                }
+
+                for scenario in self.scenarios.lock().iter() {
+                    if let Some(t) = scenario.diverged {
+                        let text =
+                            format!("{:?} diverged at t={:.3}", scenario.desc.spatial_scheme, t);
+                        ui.label(egui::RichText::new(text).color(egui::Color32::RED));
+                    }
+                    if let Some(t) = scenario.shock {
+                        let text = format!(
+                            "{:?} formed a shock at t={:.3}",
+                            scenario.desc.spatial_scheme, t
+                        );
+                        ui.label(egui::RichText::new(text).color(egui::Color32::YELLOW));
+                    }
+                }
+
+                if let Some(scenario) = self.scenarios.lock().first() {
+                    if scenario.finished() {
+                        let text = format!("Finished at t={:.3}", scenario.t);
+                        ui.label(egui::RichText::new(text).color(egui::Color32::GREEN));
+                    } else if let Some(t_final) = scenario.desc.t_final {
+                        let fraction = (scenario.t / t_final).clamp(0.0, 1.0);
+                        let text = format!("t = {:.3} / {:.3}", scenario.t, t_final);
+                        ui.add(egui::ProgressBar::new(fraction as f32).text(text));
+                    }
+                }
+
+                egui::CollapsingHeader::new("Diagnostics")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        show_diagnostics(&mut self.scenarios.lock(), ui);
+                    });
+
+                if let Some(scenario) = self.scenarios.lock().first() {
+                    let rate = f64::from_bits(self.step_rate.load(Ordering::Relaxed));
+                    let target = scenario.desc.playback_speed / scenario.dt;
+                    if 0.0 < target && !self.fixed_steps {
+                        ui.label(format!(
+                            "{:.0} steps/s ({:.0}% of target)",
+                            rate,
+                            rate / target * 100.0
+                        ));
+                    } else {
+                        ui.label(format!("{:.0} steps/s", rate));
+                    }
+                    if scenario.desc.temporal_scheme == TemporalScheme::Rk45 {
+                        ui.label(format!("dt = {:.3e}", scenario.dt))
+                            .on_hover_text("Step size chosen by the Rk45 error control");
+                    }
                 }
             });
 
-            egui_plot::Plot::new("Plotting")
+            if let Some(rows) = &self.convergence {
+                let mut open = true;
+                egui::Window::new("Convergence Study")
+                    .open(&mut open)
+                    .show(ctx, |ui| {
+                        ui.label(
+                            "Cell-integrated L2 error at t = 1, halving Delta Space and Delta Time",
+                        );
+                        egui::Grid::new("convergence").striped(true).show(ui, |ui| {
+                            ui.label("Delta Space");
+                            ui.label("L2");
+                            ui.label("Order");
+                            ui.end_row();
+
+                            for row in rows {
+                                ui.label(format!("{:.5}", row.delta_x));
+                                ui.label(format!("{:.3e}", row.l_2));
+                                match row.order {
+                                    Some(order) => ui.label(format!("{:.2}", order)),
+                                    None => ui.label("-"),
+                                };
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                if !open {
+                    self.convergence = None;
+                }
+            }
+
+            let cfl = self.desc.vel.abs() * self.desc.delta_t / self.desc.delta_x;
+            egui::Window::new("Modified Wavenumber")
+                .open(&mut self.show_dispersion)
+                .show(ctx, |ui| {
+                    ui.label(format!("k' dx versus k dx at CFL {:.2}", cfl));
+                    let curves = simulation::LINEAR_SCHEMES
+                        .iter()
+                        .zip(COLORS.iter().cycle())
+                        .map(|(scheme, color)| {
+                            (
+                                scheme,
+                                color,
+                                simulation::modified_wavenumber(*scheme, cfl, 64),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    egui_plot::Plot::new("Dispersion")
+                        .height(200.0)
+                        .legend(egui_plot::Legend::default())
+                        .show(ui, |ui| {
+                            let exact = [[0.0, 0.0], [std::f64::consts::PI, std::f64::consts::PI]];
+                            ui.line(
+                                egui_plot::Line::new(exact.to_vec())
+                                    .color(egui::Color32::GREEN)
+                                    .name("Exact"),
+                            );
+                            for (scheme, color, curve) in &curves {
+                                let points =
+                                    curve.iter().map(|w| [w.k_dx, w.real]).collect::<Vec<_>>();
+                                ui.line(
+                                    egui_plot::Line::new(points)
+                                        .color(**color)
+                                        .name(format!("Re {:?}", scheme)),
+                                );
+                            }
+                        });
+
+                    egui_plot::Plot::new("Dissipation")
+                        .height(200.0)
+                        .legend(egui_plot::Legend::default())
+                        .show(ui, |ui| {
+                            for (scheme, color, curve) in &curves {
+                                let points =
+                                    curve.iter().map(|w| [w.k_dx, w.imag]).collect::<Vec<_>>();
+                                ui.line(
+                                    egui_plot::Line::new(points)
+                                        .color(**color)
+                                        .name(format!("Im {:?}", scheme)),
+                                );
+                            }
+                        });
+                });
+
+            let history_len = self
+                .scenarios
+                .lock()
+                .first()
+                .map_or(0, |scenario| scenario.history_len());
+            if self.paused.load(Ordering::Relaxed) && 0 < history_len {
+                let mut frame = self.frame.unwrap_or(history_len - 1).min(history_len - 1);
+                ui.add(egui::Slider::new(&mut frame, 0..=history_len - 1).text("Frame"));
+                self.frame = Some(frame);
+            } else {
+                self.frame = None;
+            }
+
+            if !self.scenarios.lock().is_empty() {
+                egui_plot::Plot::new("Total Variation")
+                    .height(120.0)
+                    .legend(egui_plot::Legend::default())
+                    .show(ui, |ui| {
+                        let scenarios = self.scenarios.lock();
+                        for scenario in scenarios.iter() {
+                            let points = scenario.total_variation_history.points();
+                            let line = egui_plot::Line::new(points)
+                                .color(scenario.color)
+                                .name(format!("TV {:?}", scenario.desc.spatial_scheme));
+                            ui.line(line);
+                        }
+                    });
+            }
+
+            if self.show_peak && !self.scenarios.lock().is_empty() {
+                egui_plot::Plot::new("Peak Ratio")
+                    .height(120.0)
+                    .legend(egui_plot::Legend::default())
+                    .show(ui, |ui| {
+                        let scenarios = self.scenarios.lock();
+                        for scenario in scenarios.iter() {
+                            let points = scenario.peak_history.points();
+                            let line = egui_plot::Line::new(points)
+                                .color(scenario.color)
+                                .name(format!("Peak {:?}", scenario.desc.spatial_scheme));
+                            ui.line(line);
+                        }
+                    });
+            }
+
+            if self.show_energy && !self.scenarios.lock().is_empty() {
+                egui_plot::Plot::new("Energy")
+                    .height(120.0)
+                    .legend(egui_plot::Legend::default())
+                    .show(ui, |ui| {
+                        let scenarios = self.scenarios.lock();
+                        for scenario in scenarios.iter() {
+                            let points = scenario.energy_history.points();
+                            let line = egui_plot::Line::new(points)
+                                .color(scenario.color)
+                                .name(format!("Energy {:?}", scenario.desc.spatial_scheme));
+                            ui.line(line);
+                        }
+                    });
+            }
+
+            let (autoscale_y, y_label, header) = match self.scenarios.lock().first() {
+                Some(scenario) => (
+                    scenario.desc.autoscale_y,
+                    match scenario.desc.dimension {
+                        Dimension::One => "u(x,t)",
+                        Dimension::Two => "y",
+                    },
+                    Some((
+                        scenario.frame_time(self.frame),
+                        scenario.value_range(self.frame),
+                        scenario.initial_range(),
+                    )),
+                ),
+                None => (true, "u(x,t)", None),
+            };
+            let title = ui
+                .horizontal(|ui| {
+                    if ui
+                        .button("Fit")
+                        .on_hover_text("Reset the zoom to the extent of the data")
+                        .clicked()
+                    {
+                        self.fit_plot = true;
+                    }
+                    match header {
+                        Some((t, (min, max), (initial_min, initial_max))) => {
+                            ui.label(format!("t = {:.3}", t));
+                            let tolerance = 1e-9 * (initial_max - initial_min).abs().max(1.0);
+                            let text_color = ui.visuals().text_color();
+                            let color = |out_of_range: bool| {
+                                if out_of_range {
+                                    egui::Color32::RED
+                                } else {
+                                    text_color
+                                }
+                            };
+                            ui.colored_label(
+                                color(min < initial_min - tolerance),
+                                format!("min(u) = {:.4}", min),
+                            )
+                            .on_hover_text(format!("Initial min(u) = {:.4}", initial_min));
+                            ui.colored_label(
+                                color(max > initial_max + tolerance),
+                                format!("max(u) = {:.4}", max),
+                            )
+                            .on_hover_text(format!("Initial max(u) = {:.4}", initial_max))
+                        }
+                        None => ui.label("No scenario"),
+                    }
+                })
+                .inner;
+            let mut plot = egui_plot::Plot::new("Plotting")
                 .view_aspect(1.0)
-                .data_aspect(1.0)
-                .show(ui, |ui| {
-                    if let Some(scenario) = self.scenario.lock().as_mut() {
-                        scenario.show_inside(ui);
+                .x_axis_label("x")
+                .y_axis_label(y_label)
+                .legend(egui_plot::Legend::default());
+            if autoscale_y {
+                plot = plot.data_aspect(1.0);
+            }
+            let fit_plot = std::mem::take(&mut self.fit_plot);
+            if fit_plot {
+                plot = plot.reset();
+            }
+            let response = plot.show(ui, |ui| {
+                let mut scenarios = self.scenarios.lock();
+                // The fixed range is applied once when it changes, so zooming,
+                // panning and Fit still work until the range is edited again.
+                let fixed_bounds = scenarios
+                    .first()
+                    .map(|scenario| &scenario.desc)
+                    .filter(|desc| !desc.autoscale_y && desc.dimension == Dimension::One)
+                    .map(|desc| [0.0, desc.y_min, desc.bound, desc.y_max]);
+                if fixed_bounds != self.applied_bounds {
+                    if let Some([x_min, y_min, x_max, y_max]) = fixed_bounds {
+                        ui.set_plot_bounds(egui_plot::PlotBounds::from_min_max(
+                            [x_min, y_min],
+                            [x_max, y_max],
+                        ));
                     }
-                });
+                    self.applied_bounds = fixed_bounds;
+                }
+                if fit_plot {
+                    let mut bounds = egui_plot::PlotBounds::NOTHING;
+                    for scenario in scenarios.iter() {
+                        bounds.merge(&scenario.data_bounds(self.frame));
+                    }
+                    if bounds.is_valid() {
+                        bounds.add_relative_margin_y(egui::Vec2::splat(0.05));
+                        ui.set_plot_bounds(bounds);
+                    }
+                }
+                if let Some(scenario) = scenarios.first() {
+                    scenario.show_exact(ui, self.frame);
+                }
+                for scenario in scenarios.iter_mut() {
+                    let color = scenario.color;
+                    scenario.show_inside(ui, self.frame, color);
+                }
+            });
+            self.plot_rect = Some(response.response.rect.union(title.rect));
         })
     }
 
+    pub fn take_screenshot(&mut self) -> Option<(String, egui::Rect)> {
+        self.screenshot.take().zip(self.plot_rect)
+    }
+
+    pub fn set_status(&mut self, status: Result<String, String>) {
+        self.status = Some(status);
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused.fetch_xor(true, Ordering::Relaxed);
+        self.pending_steps.store(0, Ordering::Relaxed);
+    }
+
+    pub fn step(&mut self) {
+        if self.paused.load(Ordering::Relaxed) {
+            self.pending_steps.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.scenarios.lock().iter_mut().for_each(Scenario::reset);
+    }
+
+    fn drop_scenarios(&mut self) {
+        let scenarios = std::mem::take(&mut *self.scenarios.lock());
+        if !scenarios.is_empty() {
+            self.dropped = Some(scenarios);
+        }
+    }
+
+    fn restore_scenarios(&mut self) {
+        if let Some(mut dropped) = self.dropped.take() {
+            std::mem::swap(&mut *self.scenarios.lock(), &mut dropped);
+            if !dropped.is_empty() {
+                self.dropped = Some(dropped);
+            }
+        }
+    }
+
+    fn add_comparison(&mut self) -> Result<(), String> {
+        let mut scenarios = self.scenarios.lock();
+        let primary = scenarios
+            .first()
+            .ok_or_else(|| "Create a scenario before adding comparisons".to_string())?;
+
+        if primary.desc.dimension == Dimension::Two {
+            return Err("Comparisons are only available in one dimension".to_string());
+        }
+
+        let mut desc = primary.desc.clone();
+        desc.spatial_scheme = self.comparison_scheme;
+
+        let mut scenario = Scenario::new(desc)?;
+        scenario.advance_to(primary.t);
+        scenario.set_reversed(primary.reversed())?;
+        scenario.color = palette_color(&scenarios);
+        scenarios.push(scenario);
+        Ok(())
+    }
+
+    fn compare_temporal_schemes(&mut self) -> Result<String, String> {
+        let mut scenarios = self.scenarios.lock();
+        let primary = scenarios
+            .first()
+            .ok_or_else(|| "Create a scenario before adding comparisons".to_string())?;
+
+        if primary.desc.dimension == Dimension::Two {
+            return Err("Comparisons are only available in one dimension".to_string());
+        }
+
+        let mut skipped = vec![];
+        let comparisons = <TemporalScheme as strum::IntoEnumIterator>::iter()
+            .filter(|scheme| *scheme != primary.desc.temporal_scheme)
+            .filter_map(|temporal_scheme| {
+                let mut desc = primary.desc.clone();
+                desc.temporal_scheme = temporal_scheme;
+                if desc.max_cfl() < desc.cfl() {
+                    skipped.push(temporal_scheme);
+                    return None;
+                }
+
+                let mut scenario = Scenario::new(desc).ok().or_else(|| {
+                    skipped.push(temporal_scheme);
+                    None
+                })?;
+                scenario.advance_to(primary.t);
+                scenario.set_reversed(primary.reversed()).ok()?;
+                scenario.temporal_comparison = true;
+                Some(scenario)
+            })
+            .collect::<Vec<_>>();
+
+        // Only the previous batch is replaced, so comparisons the user added by
+        // hand survive a second run.
+        scenarios.retain(|scenario| !scenario.temporal_comparison);
+        let added = comparisons.len();
+        for mut scenario in comparisons {
+            scenario.color = palette_color(&scenarios);
+            scenarios.push(scenario);
+        }
+
+        if skipped.is_empty() {
+            Ok(format!("Added {} temporal schemes", added))
+        } else {
+            Ok(format!(
+                "Added {} temporal schemes (skipped {:?})",
+                added, skipped
+            ))
+        }
+    }
+
+    fn run_convergence_study(&mut self) {
+        let job = ConvergenceJob {
+            progress: Arc::new(simulation::Progress::default()),
+            result: Arc::new(Mutex::new(None)),
+            started: std::time::Instant::now(),
+        };
+
+        let desc = self.desc.clone();
+        let progress = job.progress.clone();
+        let result = job.result.clone();
+        std::thread::spawn(move || {
+            let rows = simulation::convergence_study(&desc, 1.0, CONVERGENCE_LEVELS, &progress);
+            *result.lock() = Some(rows);
+        });
+
+        self.convergence_job = Some(job);
+    }
+
+    pub fn update(&mut self) {
+        self.frame_driven.store(self.fixed_steps, Ordering::Relaxed);
+        if !self.fixed_steps {
+            self.last_update = None;
+            return;
+        }
+
+        let steps = if self.paused.load(Ordering::Relaxed) {
+            self.pending_steps.swap(0, Ordering::Relaxed)
+        } else {
+            self.steps_per_frame
+        };
+
+        let taken = {
+            let mut scenarios = self.scenarios.lock();
+            let taken = forward_batch(&mut scenarios, steps).map_or(0, |(_, n)| n);
+            if 0 < taken && scenarios.iter().any(Scenario::shock_formed) {
+                self.paused.store(true, Ordering::Relaxed);
+            }
+            taken
+        };
+
+        let instant = std::time::Instant::now();
+        let rate = match self.last_update {
+            Some(last_update) if 0 < taken => {
+                let elapsed = instant.duration_since(last_update).as_secs_f64();
+                let previous = f64::from_bits(self.step_rate.load(Ordering::Relaxed));
+                0.9 * previous + 0.1 * taken as f64 / elapsed
+            }
+            _ => 0.0,
+        };
+        self.step_rate.store(rate.to_bits(), Ordering::Relaxed);
+        self.last_update = Some(instant);
+    }
+
     pub fn spawn_thread(&mut self) -> std::thread::JoinHandle<()> {
         const WAIT_TIME: f64 = 0.001;
+        const MIN_PLAYBACK_SPEED: f64 = 0.01;
 
-        let scenario = self.scenario.clone();
+        let scenarios = self.scenarios.clone();
+        let paused = self.paused.clone();
+        let pending_steps = self.pending_steps.clone();
+        let step_rate = self.step_rate.clone();
+        let substeps = self.substeps.clone();
+        let frame_driven = self.frame_driven.clone();
+        let mut last_step = None::<std::time::Instant>;
+        let mut period = 0.0;
         std::thread::spawn(move || loop {
+            if frame_driven.load(Ordering::Relaxed) {
+                last_step = None;
+                period = 0.0;
+                std::thread::sleep(std::time::Duration::from_secs_f64(WAIT_TIME));
+                continue;
+            }
+
             let instant = std::time::Instant::now();
 
-            let wait_time = if let Some(scenario) = scenario.lock().as_mut() {
-                scenario.forward();
+            let running = !paused.load(Ordering::Relaxed);
+            let step = running
+                || pending_steps
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                    .is_ok();
+            let steps = if running {
+                substeps.load(Ordering::Relaxed).max(1)
+            } else {
+                1
+            };
 
-                let elapsed = instant.elapsed().as_secs_f64();
-                let wait_time =
-                    (scenario.desc.delta_t - elapsed).max(0.0) / scenario.desc.time_scale;
-                wait_time
+            let mut guard = scenarios.lock();
+            let playback_speed = guard
+                .first()
+                .map_or(0.0, |scenario| scenario.desc.playback_speed);
+            let batch = if step && MIN_PLAYBACK_SPEED <= playback_speed {
+                forward_batch(&mut guard, steps)
             } else {
-                WAIT_TIME
+                None
+            };
+            if batch.is_some() && guard.iter().any(Scenario::shock_formed) {
+                paused.store(true, Ordering::Relaxed);
+                pending_steps.store(0, Ordering::Relaxed);
+            }
+            drop(guard);
+
+            let wait_time = match batch {
+                Some((dt, taken)) => {
+                    if let Some(last_step) = last_step {
+                        let elapsed = instant.duration_since(last_step).as_secs_f64();
+                        period = if period == 0.0 {
+                            elapsed
+                        } else {
+                            0.9 * period + 0.1 * elapsed
+                        };
+                        step_rate.store((taken as f64 / period).to_bits(), Ordering::Relaxed);
+                    }
+                    last_step = Some(instant);
+
+                    let elapsed = instant.elapsed().as_secs_f64();
+                    (dt - elapsed).max(0.0) / playback_speed
+                }
+                None => {
+                    last_step = None;
+                    period = 0.0;
+                    step_rate.store(0.0_f64.to_bits(), Ordering::Relaxed);
+                    WAIT_TIME
+                }
             };
 
             std::thread::sleep(std::time::Duration::from_secs_f64(wait_time));
         })
     }
 }
+
+fn palette_color(scenarios: &[Scenario]) -> egui::Color32 {
+    COLORS
+        .iter()
+        .copied()
+        .find(|color| scenarios.iter().all(|scenario| scenario.color != *color))
+        .unwrap_or(COLORS[scenarios.len() % COLORS.len()])
+}
+
+fn show_diagnostics(scenarios: &mut [Scenario], ui: &mut egui::Ui) {
+    let Some(primary) = scenarios.first() else {
+        ui.label("No scenario");
+        return;
+    };
+
+    const METRICS: [&str; 12] = [
+        "Mass",
+        "Mass Drift",
+        "Total Variation",
+        "Energy",
+        "L1",
+        "L2",
+        "Linf",
+        "L2 (quad)",
+        "Centroid",
+        "Phase Error",
+        "Peak Ratio",
+        "Ops/step",
+    ];
+    let columns = scenarios
+        .iter()
+        .map(|scenario| {
+            let errors = scenario.errors();
+            [
+                format!("{:.4}", scenario.total_mass()),
+                format!("{:+.2e}", scenario.mass_drift()),
+                format!("{:.4}", scenario.total_variation()),
+                format!("{:.4}", scenario.energy()),
+                format!("{:.3e}", errors.l_1),
+                format!("{:.3e}", errors.l_2),
+                format!("{:.3e}", errors.l_inf),
+                format!("{:.3e}", errors.l_2_quad),
+                format!("{:.3}", scenario.centroid()),
+                format!("{:+.3e}", errors.phase),
+                format!("{:.4}", scenario.peak_ratio()),
+                format!("{}", scenario.desc.ops_per_step()),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    ui.label(format!("t = {:.3}", primary.t));
+    ui.label(format!("errors at t = {:.3}", primary.errors().t))
+        .on_hover_text("Error norms refresh every Diagnostic Stride steps");
+    egui::Grid::new("diagnostics").striped(true).show(ui, |ui| {
+        ui.label("");
+        for scenario in scenarios.iter_mut() {
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba(&mut scenario.color);
+                let name = format!("{:?}", scenario.desc.spatial_scheme);
+                ui.label(egui::RichText::new(name).color(scenario.color));
+            });
+        }
+        ui.end_row();
+
+        for (row, name) in METRICS.iter().enumerate() {
+            ui.label(*name);
+            for column in &columns {
+                ui.label(&column[row]);
+            }
+            ui.end_row();
+        }
+    });
+}
+
+fn forward_batch(scenarios: &mut [Scenario], steps: usize) -> Option<(f64, usize)> {
+    let mut batch = None;
+    for _ in 0..steps {
+        match simulation::forward_all(scenarios) {
+            Some(dt) => {
+                let (t, taken) = batch.unwrap_or((0.0, 0));
+                batch = Some((t + dt, taken + 1));
+                if scenarios.iter().any(Scenario::shock_formed) {
+                    break;
+                }
+            }
+            None => break,
+        }
+    }
+    batch
+}
@@ -0,0 +1,167 @@
+use crate::simulation::{Descriptor, Scenario};
+
+pub const USAGE: &str = "\
+usage: advection-simulation [options]
+
+options:
+  --config <path>       load parameters from a JSON config file that other flags override
+  --scheme <name>       spatial scheme (e.g. WENO)
+  --temporal <name>     temporal scheme (e.g. TvdRk3)
+  --equation <name>     equation (LinearAdvection or Burgers)
+  --initial <name>      initial condition (e.g. Gaussian)
+  --boundary <name>     boundary condition (e.g. Periodic)
+  --dx <value>          grid spacing
+  --dt <value>          time step
+  --vel <value>         advection velocity
+  --tfinal <value>      final time of the run
+  --out <path>          write the final state as CSV and skip the GUI
+  --headless            skip the GUI and write <file stem>.csv
+  --help                print this message";
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub desc: Descriptor,
+    pub out: Option<String>,
+    pub headless: bool,
+    pub help: bool,
+}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options {
+        desc: Descriptor::new(),
+        out: None,
+        headless: false,
+        help: false,
+    };
+
+    // The config is the base that every other flag overrides, wherever it
+    // appears on the command line.
+    let args = args.into_iter().collect::<Vec<_>>();
+    let configs = args
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| *arg == "--config")
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    match configs.as_slice() {
+        [] => {}
+        [i] => {
+            let path = args.get(i + 1).ok_or("missing value for --config")?;
+            options.desc = crate::config::load(path)?;
+        }
+        _ => return Err("--config given more than once".to_string()),
+    }
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+
+        let desc = &mut options.desc;
+        match flag.as_str() {
+            "--config" => {
+                value()?;
+            }
+            "--scheme" => desc.spatial_scheme = parse_enum(&flag, &value()?)?,
+            "--temporal" => desc.temporal_scheme = parse_enum(&flag, &value()?)?,
+            "--equation" => desc.equation = parse_enum(&flag, &value()?)?,
+            "--initial" => desc.initial_condition = parse_enum(&flag, &value()?)?,
+            "--boundary" => desc.boundary_condition = parse_enum(&flag, &value()?)?,
+            "--dx" => desc.delta_x = parse_number(&flag, &value()?)?,
+            "--dt" => desc.delta_t = parse_number(&flag, &value()?)?,
+            "--vel" => desc.vel = parse_number(&flag, &value()?)?,
+            "--tfinal" => desc.t_final = Some(parse_number(&flag, &value()?)?),
+            "--out" => options.out = Some(value()?),
+            "--headless" => options.headless = true,
+            "--help" | "-h" => options.help = true,
+            _ => return Err(format!("unknown argument \"{}\"\n\n{}", flag, USAGE)),
+        }
+    }
+
+    options.headless |= options.out.is_some();
+    Ok(options)
+}
+
+pub fn run(options: &Options) -> Result<String, String> {
+    let t_final = options
+        .desc
+        .t_final
+        .ok_or("headless runs require --tfinal")?;
+
+    let mut scenario = Scenario::new(options.desc.clone())?;
+    scenario.advance_to(t_final);
+    if let Some(t) = scenario.diverged {
+        return Err(format!("diverged at t={:.3}", t));
+    }
+
+    let path = match &options.out {
+        Some(path) => path.clone(),
+        None => format!("{}.csv", scenario.file_stem()),
+    };
+    scenario.export_csv(&path).map_err(|e| e.to_string())?;
+
+    let (l_1, l_2, l_inf) = scenario.error_norms();
+    Ok(format!(
+        "wrote {} at t={:.3} (t_final {}) | L1 {:.3e} | L2 {:.3e} | Linf {:.3e}",
+        path, scenario.t, t_final, l_1, l_2, l_inf
+    ))
+}
+
+fn parse_enum<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("unknown variant \"{}\" for {}", value, flag))
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<f64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number \"{}\" for {}", value, flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_override_the_config_in_any_order() {
+        let path = std::env::temp_dir().join("advection_cli_config.json");
+        let path = path.to_str().unwrap();
+        let mut desc = Descriptor::new();
+        desc.vel = 2.0;
+        desc.delta_x = 0.1;
+        crate::config::save(path, &desc).unwrap();
+
+        for order in [
+            ["--dx", "0.025", "--config", path],
+            ["--config", path, "--dx", "0.025"],
+        ] {
+            let options = parse(args(&order)).unwrap();
+            assert_eq!(options.desc.vel, 2.0);
+            assert_eq!(options.desc.delta_x, 0.025);
+        }
+
+        let error = parse(args(&["--config", path, "--config", path])).unwrap_err();
+        assert_eq!(error, "--config given more than once");
+        assert_eq!(
+            parse(args(&["--config"])).unwrap_err(),
+            "missing value for --config"
+        );
+    }
+
+    #[test]
+    fn run_stops_exactly_at_t_final() {
+        let out = std::env::temp_dir().join("advection_cli_run.csv");
+        let out = out.to_str().unwrap();
+        let options = parse(args(&["--dt", "0.03", "--tfinal", "1", "--out", out])).unwrap();
+
+        let summary = run(&options).unwrap();
+        assert!(summary.contains("at t=1.000 "), "{}", summary);
+    }
+}
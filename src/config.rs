@@ -0,0 +1,431 @@
+use crate::expression::Expression;
+use crate::simulation::Descriptor;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Null,
+}
+
+impl Value {
+    fn as_f64(&self, key: &str) -> Result<f64, String> {
+        match self {
+            Value::Number(value) => Ok(*value),
+            _ => Err(format!("expected a number for \"{}\"", key)),
+        }
+    }
+
+    fn as_usize(&self, key: &str) -> Result<usize, String> {
+        match self {
+            Value::Number(value) if is_exact_integer(*value) => Ok(*value as usize),
+            _ => Err(format!("expected a non-negative integer for \"{}\"", key)),
+        }
+    }
+
+    fn as_u64(&self, key: &str) -> Result<u64, String> {
+        match self {
+            Value::String(value) => value
+                .parse()
+                .map_err(|_| format!("expected a non-negative integer for \"{}\"", key)),
+            Value::Number(value) if is_exact_integer(*value) => Ok(*value as u64),
+            _ => Err(format!("expected a non-negative integer for \"{}\"", key)),
+        }
+    }
+
+    fn as_bool(&self, key: &str) -> Result<bool, String> {
+        match self {
+            Value::Bool(value) => Ok(*value),
+            _ => Err(format!("expected a bool for \"{}\"", key)),
+        }
+    }
+
+    fn as_string(&self, key: &str) -> Result<String, String> {
+        match self {
+            Value::String(value) => Ok(value.clone()),
+            _ => Err(format!("expected a string for \"{}\"", key)),
+        }
+    }
+
+    fn as_enum<T: std::str::FromStr>(&self, key: &str) -> Result<T, String> {
+        match self {
+            Value::String(value) => value
+                .parse()
+                .map_err(|_| format!("unknown variant \"{}\" for \"{}\"", value, key)),
+            _ => Err(format!("expected a string for \"{}\"", key)),
+        }
+    }
+}
+
+// Above 2^53 an f64 no longer holds every integer, so larger numbers are
+// rejected rather than silently rounded.
+fn is_exact_integer(value: f64) -> bool {
+    (0.0..=9007199254740992.0).contains(&value) && value.fract() == 0.0
+}
+
+pub fn save(path: &str, desc: &Descriptor) -> Result<(), String> {
+    std::fs::write(path, to_json(desc)).map_err(|e| e.to_string())
+}
+
+pub fn load(path: &str) -> Result<Descriptor, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    from_json(&text)
+}
+
+fn entries(desc: &Descriptor) -> Vec<(&'static str, Value)> {
+    let name = |value: &dyn std::fmt::Debug| Value::String(format!("{:?}", value));
+
+    vec![
+        ("playback_speed", Value::Number(desc.playback_speed)),
+        ("delta_t", Value::Number(desc.delta_t)),
+        ("delta_x", Value::Number(desc.delta_x)),
+        ("bound", Value::Number(desc.bound)),
+        ("stretching", Value::Number(desc.stretching)),
+        ("x_1", Value::Number(desc.x_1)),
+        ("x_2", Value::Number(desc.x_2)),
+        ("vel", Value::Number(desc.vel)),
+        ("vel_y", Value::Number(desc.vel_y)),
+        ("velocity_field", name(&desc.velocity_field)),
+        ("vel_freq", Value::Number(desc.vel_freq)),
+        ("form", name(&desc.form)),
+        ("dimension", name(&desc.dimension)),
+        ("initial_condition", name(&desc.initial_condition)),
+        ("equation", name(&desc.equation)),
+        ("spatial_scheme", name(&desc.spatial_scheme)),
+        ("temporal_scheme", name(&desc.temporal_scheme)),
+        ("limiter", name(&desc.limiter)),
+        ("boundary_condition", name(&desc.boundary_condition)),
+        ("plot_style", name(&desc.plot_style)),
+        ("show_limiter", Value::Bool(desc.show_limiter)),
+        ("show_weights", Value::Bool(desc.show_weights)),
+        ("fct", Value::Bool(desc.fct)),
+        ("break_on_shock", Value::Bool(desc.break_on_shock)),
+        ("show_gradient", Value::Bool(desc.show_gradient)),
+        ("show_initial", Value::Bool(desc.show_initial)),
+        ("central_order", Value::Number(desc.central_order as f64)),
+        ("adaptive", Value::Bool(desc.adaptive)),
+        ("target_cfl", Value::Number(desc.target_cfl)),
+        ("tolerance", Value::Number(desc.tolerance)),
+        ("record_history", Value::Bool(desc.record_history)),
+        ("history_length", Value::Number(desc.history_length as f64)),
+        (
+            "diagnostic_stride",
+            Value::Number(desc.diagnostic_stride as f64),
+        ),
+        ("amplitude", Value::Number(desc.amplitude)),
+        ("sigma", Value::Number(desc.sigma)),
+        ("grid_mode", name(&desc.grid_mode)),
+        ("cells", Value::Number(desc.cells as f64)),
+        ("autoscale_y", Value::Bool(desc.autoscale_y)),
+        ("y_min", Value::Number(desc.y_min)),
+        ("y_max", Value::Number(desc.y_max)),
+        ("t_final", desc.t_final.map_or(Value::Null, Value::Number)),
+        ("seed", Value::String(desc.seed.to_string())),
+        (
+            "expression",
+            Value::String(desc.expression.source().to_string()),
+        ),
+        ("center_1", Value::Number(desc.center_1)),
+        ("amplitude_1", Value::Number(desc.amplitude_1)),
+        ("center_2", Value::Number(desc.center_2)),
+        ("amplitude_2", Value::Number(desc.amplitude_2)),
+        (
+            "clamp_min",
+            desc.clamp_bounds
+                .map_or(Value::Null, |(min, _)| Value::Number(min)),
+        ),
+        (
+            "clamp_max",
+            desc.clamp_bounds
+                .map_or(Value::Null, |(_, max)| Value::Number(max)),
+        ),
+    ]
+}
+
+fn apply(desc: &mut Descriptor, key: &str, value: &Value) -> Result<(), String> {
+    match key {
+        "playback_speed" | "time_scale" => desc.playback_speed = value.as_f64(key)?,
+        "delta_t" => desc.delta_t = value.as_f64(key)?,
+        "delta_x" => desc.delta_x = value.as_f64(key)?,
+        "bound" => desc.bound = value.as_f64(key)?,
+        "stretching" => desc.stretching = value.as_f64(key)?,
+        "x_1" => desc.x_1 = value.as_f64(key)?,
+        "x_2" => desc.x_2 = value.as_f64(key)?,
+        "vel" => desc.vel = value.as_f64(key)?,
+        "vel_y" => desc.vel_y = value.as_f64(key)?,
+        "velocity_field" => desc.velocity_field = value.as_enum(key)?,
+        "vel_freq" => desc.vel_freq = value.as_f64(key)?,
+        "form" => desc.form = value.as_enum(key)?,
+        "dimension" => desc.dimension = value.as_enum(key)?,
+        "initial_condition" => desc.initial_condition = value.as_enum(key)?,
+        "equation" => desc.equation = value.as_enum(key)?,
+        "spatial_scheme" => desc.spatial_scheme = value.as_enum(key)?,
+        "temporal_scheme" => desc.temporal_scheme = value.as_enum(key)?,
+        "limiter" => desc.limiter = value.as_enum(key)?,
+        "boundary_condition" => desc.boundary_condition = value.as_enum(key)?,
+        "plot_style" => desc.plot_style = value.as_enum(key)?,
+        "show_limiter" => desc.show_limiter = value.as_bool(key)?,
+        "show_weights" => desc.show_weights = value.as_bool(key)?,
+        "fct" => desc.fct = value.as_bool(key)?,
+        "break_on_shock" => desc.break_on_shock = value.as_bool(key)?,
+        "show_gradient" => desc.show_gradient = value.as_bool(key)?,
+        "show_initial" => desc.show_initial = value.as_bool(key)?,
+        "central_order" => desc.central_order = value.as_usize(key)?,
+        "adaptive" => desc.adaptive = value.as_bool(key)?,
+        "target_cfl" => desc.target_cfl = value.as_f64(key)?,
+        "tolerance" => desc.tolerance = value.as_f64(key)?,
+        "record_history" => desc.record_history = value.as_bool(key)?,
+        "history_length" => desc.history_length = value.as_usize(key)?,
+        "diagnostic_stride" => desc.diagnostic_stride = value.as_usize(key)?,
+        "amplitude" => desc.amplitude = value.as_f64(key)?,
+        "sigma" => desc.sigma = value.as_f64(key)?,
+        "grid_mode" => desc.grid_mode = value.as_enum(key)?,
+        "cells" => desc.cells = value.as_usize(key)?,
+        "autoscale_y" => desc.autoscale_y = value.as_bool(key)?,
+        "y_min" => desc.y_min = value.as_f64(key)?,
+        "y_max" => desc.y_max = value.as_f64(key)?,
+        "seed" => desc.seed = value.as_u64(key)?,
+        "expression" => desc.expression = Expression::new(&value.as_string(key)?),
+        "center_1" => desc.center_1 = value.as_f64(key)?,
+        "amplitude_1" => desc.amplitude_1 = value.as_f64(key)?,
+        "center_2" => desc.center_2 = value.as_f64(key)?,
+        "amplitude_2" => desc.amplitude_2 = value.as_f64(key)?,
+        "t_final" => {
+            desc.t_final = match value {
+                Value::Null => None,
+                value => Some(value.as_f64(key)?),
+            }
+        }
+        "clamp_min" | "clamp_max" => {
+            desc.clamp_bounds = match value {
+                Value::Null => None,
+                value => {
+                    let (min, max) = desc.clamp_bounds.unwrap_or((0.0, desc.amplitude));
+                    let bound = value.as_f64(key)?;
+                    Some(if key == "clamp_min" {
+                        (bound, max)
+                    } else {
+                        (min, bound)
+                    })
+                }
+            }
+        }
+        _ => return Err(format!("unknown key \"{}\"", key)),
+    }
+
+    Ok(())
+}
+
+fn to_json(desc: &Descriptor) -> String {
+    let entries = entries(desc)
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::Number(value) if value.is_finite() => format!("{}", value),
+                Value::String(value) => format!("{:?}", value),
+                Value::Bool(value) => format!("{}", value),
+                Value::Number(_) | Value::Null => "null".to_string(),
+            };
+            format!("  \"{}\": {}", key, value)
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+fn from_json(text: &str) -> Result<Descriptor, String> {
+    let mut desc = Descriptor::new();
+    for (key, value) in Parser::new(text).parse_object()? {
+        apply(&mut desc, &key, &value)?;
+    }
+    Ok(desc)
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected '{}', found '{}'", expected, c)),
+            None => Err(format!("expected '{}', found end of input", expected)),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Vec<(String, Value)>, String> {
+        let mut entries = vec![];
+
+        self.expect('{')?;
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Ok(entries);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            entries.push((key, value));
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                Some(c) => return Err(format!("expected ',' or '}}', found '{}'", c)),
+                None => return Err("unterminated object".to_string()),
+            }
+        }
+
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) => Err(format!("unexpected trailing '{}'", c)),
+            None => Ok(entries),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('"') => Ok(Value::String(self.parse_string()?)),
+            Some(c) if *c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    _ => Err(format!("unexpected token \"{}\"", word)),
+                }
+            }
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, String> {
+        let mut number = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            number.push(c);
+        }
+        number
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| format!("invalid number \"{}\"", number))
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let c = match self.chars.next() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('u') => {
+                            let code = (0..4).filter_map(|_| self.chars.next()).collect::<String>();
+                            u32::from_str_radix(&code, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| format!("invalid escape \"\\u{}\"", code))?
+                        }
+                        Some(c) => c,
+                        None => return Err("unterminated string".to_string()),
+                    };
+                    string.push(c);
+                }
+                Some(c) => string.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descriptor_round_trips_through_json() {
+        let mut desc = Descriptor::new();
+        desc.seed = u64::MAX - 1;
+        desc.cells = 17;
+        desc.diagnostic_stride = 3;
+        desc.t_final = Some(2.5);
+        desc.expression = Expression::new("sin(x) / \"2\"");
+
+        let json = to_json(&desc);
+        let loaded = from_json(&json).unwrap();
+        assert_eq!(loaded.seed, u64::MAX - 1);
+        assert_eq!(loaded.cells, 17);
+        assert_eq!(loaded.diagnostic_stride, 3);
+        assert_eq!(loaded.t_final, Some(2.5));
+        assert_eq!(loaded.expression.source(), desc.expression.source());
+        assert_eq!(to_json(&loaded), json);
+    }
+
+    #[test]
+    fn integer_fields_accept_only_non_negative_integers() {
+        assert_eq!(from_json(r#"{"cells": 40}"#).unwrap().cells, 40);
+        assert_eq!(from_json(r#"{"seed": 12345}"#).unwrap().seed, 12345);
+
+        for text in [
+            r#"{"cells": -1}"#,
+            r#"{"diagnostic_stride": 2.5}"#,
+            r#"{"history_length": 1e300}"#,
+            r#"{"seed": 18446744073709551615}"#,
+            r#"{"seed": "-3"}"#,
+        ] {
+            let error = from_json(text).unwrap_err();
+            assert!(
+                error.starts_with("expected a non-negative integer"),
+                "{}: {}",
+                text,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_json_is_rejected() {
+        for (text, error) in [
+            (r#"{"cells": 40,}"#, "expected '\"', found '}'"),
+            (r#"{"expression": "x"#, "unterminated string"),
+            (r#"{"cells": 40"#, "unterminated object"),
+            (r#"{"cells": 40} 1"#, "unexpected trailing '1'"),
+            (r#"{"cells": yes}"#, "unexpected token \"yes\""),
+            (r#"{"cells": 4-0}"#, "invalid number \"4-0\""),
+            (r#"{"colour": 1}"#, "unknown key \"colour\""),
+            (
+                r#"{"cells": "40"}"#,
+                "expected a non-negative integer for \"cells\"",
+            ),
+        ] {
+            assert_eq!(from_json(text).unwrap_err(), error, "{}", text);
+        }
+    }
+}
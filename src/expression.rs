@@ -0,0 +1,272 @@
+type Function = fn(f64) -> f64;
+
+const FUNCTIONS: [(&str, Function); 13] = [
+    ("sin", f64::sin),
+    ("cos", f64::cos),
+    ("tan", f64::tan),
+    ("sinh", f64::sinh),
+    ("cosh", f64::cosh),
+    ("tanh", f64::tanh),
+    ("exp", f64::exp),
+    ("ln", f64::ln),
+    ("log", f64::log10),
+    ("sqrt", f64::sqrt),
+    ("abs", f64::abs),
+    ("floor", f64::floor),
+    ("sign", f64::signum),
+];
+
+#[derive(Clone, Debug)]
+pub struct Expression {
+    source: String,
+    node: Result<Node, String>,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Number(f64),
+    X,
+    Neg(Box<Node>),
+    Binary(char, Box<Node>, Box<Node>),
+    Call(Function, Box<Node>),
+}
+
+impl Expression {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            node: Parser::new(source).parse(),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.node.as_ref().err().map(String::as_str)
+    }
+
+    pub fn eval(&self, x: f64) -> f64 {
+        match &self.node {
+            Ok(node) => node.eval(x),
+            Err(_) => f64::NAN,
+        }
+    }
+}
+
+impl Node {
+    fn eval(&self, x: f64) -> f64 {
+        match self {
+            Node::Number(value) => *value,
+            Node::X => x,
+            Node::Neg(node) => -node.eval(x),
+            Node::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(x), rhs.eval(x));
+                match op {
+                    '+' => lhs + rhs,
+                    '-' => lhs - rhs,
+                    '*' => lhs * rhs,
+                    '/' => lhs / rhs,
+                    _ => lhs.powf(rhs),
+                }
+            }
+            Node::Call(f, node) => f(node.eval(x)),
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn next_op(&mut self, ops: &[char]) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.next_if(|c| ops.contains(c))
+    }
+
+    fn parse(&mut self) -> Result<Node, String> {
+        let node = self.parse_sum()?;
+
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Ok(node),
+        }
+    }
+
+    fn parse_sum(&mut self) -> Result<Node, String> {
+        let mut node = self.parse_product()?;
+        while let Some(op) = self.next_op(&['+', '-']) {
+            node = Node::Binary(op, Box::new(node), Box::new(self.parse_product()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_product(&mut self) -> Result<Node, String> {
+        let mut node = self.parse_unary()?;
+        while let Some(op) = self.next_op(&['*', '/']) {
+            node = Node::Binary(op, Box::new(node), Box::new(self.parse_unary()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_unary(&mut self) -> Result<Node, String> {
+        match self.next_op(&['-', '+']) {
+            Some('-') => Ok(Node::Neg(Box::new(self.parse_unary()?))),
+            Some(_) => self.parse_unary(),
+            None => self.parse_power(),
+        }
+    }
+
+    fn parse_power(&mut self) -> Result<Node, String> {
+        let base = self.parse_atom()?;
+        match self.next_op(&['^']) {
+            Some(op) => Ok(Node::Binary(
+                op,
+                Box::new(base),
+                Box::new(self.parse_unary()?),
+            )),
+            None => Ok(base),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('(') => {
+                self.chars.next();
+                let node = self.parse_sum()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Some(c) if c.is_ascii_digit() || *c == '.' => self.parse_number(),
+            Some(c) if c.is_ascii_alphabetic() => self.parse_word(),
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Node, String> {
+        let mut number = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            number.push(c);
+        }
+
+        // Only take the exponent when digits follow, so that "2e" still
+        // reports the stray constant instead of a malformed number.
+        let mut lookahead = self.chars.clone();
+        if lookahead.next_if(|c| matches!(c, 'e' | 'E')).is_some() {
+            lookahead.next_if(|c| matches!(c, '+' | '-'));
+            if lookahead.peek().is_some_and(|c| c.is_ascii_digit()) {
+                while let Some(c) = self.chars.next_if(|c| !c.is_ascii_digit()) {
+                    number.push(c);
+                }
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit()) {
+                    number.push(c);
+                }
+            }
+        }
+
+        number
+            .parse()
+            .map(Node::Number)
+            .map_err(|_| format!("invalid number \"{}\"", number))
+    }
+
+    fn parse_word(&mut self) -> Result<Node, String> {
+        let mut word = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphanumeric()) {
+            word.push(c);
+        }
+
+        match word.as_str() {
+            "x" => return Ok(Node::X),
+            "pi" => return Ok(Node::Number(std::f64::consts::PI)),
+            "e" => return Ok(Node::Number(std::f64::consts::E)),
+            _ => {}
+        }
+
+        let (_, f) = FUNCTIONS
+            .iter()
+            .find(|(name, _)| *name == word)
+            .ok_or_else(|| format!("unknown name \"{}\"", word))?;
+        self.expect('(')?;
+        let node = self.parse_sum()?;
+        self.expect(')')?;
+        Ok(Node::Call(*f, Box::new(node)))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected '{}', found '{}'", expected, c)),
+            None => Err(format!("expected '{}', found end of expression", expected)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str) -> f64 {
+        let expression = Expression::new(source);
+        assert_eq!(expression.error(), None, "{}", source);
+        expression.eval(2.0)
+    }
+
+    fn error(source: &str) -> String {
+        Expression::new(source).error().unwrap().to_string()
+    }
+
+    #[test]
+    fn operators_follow_the_usual_precedence() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("8 / 4 / 2"), 1.0);
+        assert_eq!(eval("7 - 2 - 1"), 4.0);
+        assert_eq!(eval("2 * x ^ 2"), 8.0);
+        assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(eval("-2 ^ 2"), -4.0);
+        assert_eq!(eval("2 ^ -1"), 0.5);
+        assert_eq!(eval("--x"), 2.0);
+        assert_eq!(eval("3 * -x"), -6.0);
+    }
+
+    #[test]
+    fn numbers_accept_an_exponent() {
+        assert_eq!(eval("1e-3"), 1e-3);
+        assert_eq!(eval("2.5E+2"), 250.0);
+        assert_eq!(eval("3e2 * x"), 600.0);
+        assert_eq!(eval(".5"), 0.5);
+        assert_eq!(eval("2*e"), 2.0 * std::f64::consts::E);
+        assert_eq!(eval("exp(1)"), std::f64::consts::E);
+        assert_eq!(eval("sqrt(abs(-16)) + pi - pi"), 4.0);
+    }
+
+    #[test]
+    fn errors_name_the_offending_input() {
+        assert_eq!(error("foo(x)"), "unknown name \"foo\"");
+        assert_eq!(error("y"), "unknown name \"y\"");
+        assert_eq!(error("2e"), "unexpected 'e'");
+        assert_eq!(error("1.2.3"), "invalid number \"1.2.3\"");
+        assert_eq!(error("sin x"), "expected '(', found 'x'");
+        assert_eq!(error("(1 + x"), "expected ')', found end of expression");
+        assert_eq!(error("1 +"), "unexpected end of expression");
+        assert_eq!(error("x $ 2"), "unexpected '$'");
+        assert!(Expression::new("foo").eval(1.0).is_nan());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod cli;
+pub mod config;
+pub mod expression;
+pub mod png;
+pub mod simulation;
+
+pub use simulation::{Descriptor, Scenario, SpatialScheme, TemporalScheme};
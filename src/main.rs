@@ -1,7 +1,7 @@
+use advection_simulation::cli;
 use miniquad as mq;
 
 mod body;
-mod simulation;
 
 pub struct State {
     egui_mq: egui_miniquad::EguiMq,
@@ -11,9 +11,9 @@ pub struct State {
 }
 
 impl State {
-    fn new() -> Self {
+    fn new(desc: advection_simulation::Descriptor) -> Self {
         let mut mq_ctx = mq::window::new_rendering_backend();
-        let mut widget = body::Body::new();
+        let mut widget = body::Body::new(desc);
 
         Self {
             egui_mq: egui_miniquad::EguiMq::new(mq_ctx.as_mut()),
@@ -24,8 +24,49 @@ impl State {
     }
 }
 
+impl State {
+    fn read_pixels(&self, rect: egui::Rect) -> Result<(u32, u32, Vec<u8>), String> {
+        if self.mq_ctx.info().backend != mq::Backend::OpenGl {
+            return Err("PNG export requires the OpenGL backend".to_string());
+        }
+
+        let scale = self.egui_mq.egui_ctx().pixels_per_point();
+        let (screen_width, screen_height) = mq::window::screen_size();
+        let rect = egui::Rect::from_min_max(rect.min * scale, rect.max * scale).intersect(
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(screen_width, screen_height)),
+        );
+        let (width, height) = (rect.width() as u32, rect.height() as u32);
+        if width == 0 || height == 0 {
+            return Err("The plot is not visible".to_string());
+        }
+
+        let mut pixels = vec![0_u8; width as usize * height as usize * 4];
+        unsafe {
+            mq::gl::glReadPixels(
+                rect.min.x as i32,
+                (screen_height - rect.max.y) as i32,
+                width as i32,
+                height as i32,
+                mq::gl::GL_RGBA,
+                mq::gl::GL_UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut _,
+            );
+        }
+
+        let rgba = pixels
+            .chunks(width as usize * 4)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        Ok((width, height, rgba))
+    }
+}
+
 impl miniquad::EventHandler for State {
-    fn update(&mut self) {}
+    fn update(&mut self) {
+        self.widget.update();
+    }
 
     fn draw(&mut self) {
         self.mq_ctx
@@ -38,6 +79,17 @@ impl miniquad::EventHandler for State {
 
         self.egui_mq.draw(self.mq_ctx.as_mut());
 
+        if let Some((path, rect)) = self.widget.take_screenshot() {
+            let status = self
+                .read_pixels(rect)
+                .and_then(|(width, height, rgba)| {
+                    advection_simulation::png::save(&path, width, height, &rgba)
+                        .map_err(|e| e.to_string())
+                })
+                .map(|_| format!("Exported {}", path));
+            self.widget.set_status(status);
+        }
+
         self.mq_ctx.commit_frame();
     }
 
@@ -68,9 +120,20 @@ impl miniquad::EventHandler for State {
         &mut self,
         keycode: miniquad::KeyCode,
         keymods: miniquad::KeyMods,
-        _repeat: bool,
+        repeat: bool,
     ) {
         self.egui_mq.key_down_event(keycode, keymods);
+
+        if self.egui_mq.egui_ctx().wants_keyboard_input() {
+            return;
+        }
+
+        match keycode {
+            miniquad::KeyCode::Space if !repeat => self.widget.toggle_pause(),
+            miniquad::KeyCode::Right => self.widget.step(),
+            miniquad::KeyCode::R if !repeat => self.widget.reset(),
+            _ => {}
+        }
     }
 
     fn key_up_event(&mut self, keycode: miniquad::KeyCode, keymods: miniquad::KeyMods) {
@@ -79,6 +142,30 @@ impl miniquad::EventHandler for State {
 }
 
 fn main() {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    if options.headless {
+        match cli::run(&options) {
+            Ok(summary) => println!("{}", summary),
+            Err(message) => {
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let conf = mq::conf::Conf {
         window_title: "physics-simulation".into(),
         window_width: 1280,
@@ -88,5 +175,5 @@ fn main() {
         ..Default::default()
     };
 
-    mq::start(conf, || Box::new(State::new()));
+    mq::start(conf, || Box::new(State::new(options.desc)));
 }
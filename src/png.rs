@@ -0,0 +1,153 @@
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const MAX_STORED_BLOCK: usize = 0xffff;
+
+pub fn save(path: &str, width: u32, height: u32, rgba: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, encode(width, height, rgba))
+}
+
+pub fn encode(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    assert_eq!(rgba.len(), width as usize * height as usize * 4);
+
+    let mut header = vec![];
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut scanlines = vec![];
+    for row in rgba.chunks(width as usize * 4) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for &byte in data {
+        a = (a + byte as u32) % MODULUS;
+        b = (b + a) % MODULUS;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inflate_stored(zlib: &[u8]) -> Vec<u8> {
+        assert_eq!(&zlib[..2], &[0x78, 0x01]);
+
+        let (mut data, mut at) = (vec![], 2);
+        loop {
+            let last = zlib[at] == 1;
+            let len = u16::from_le_bytes([zlib[at + 1], zlib[at + 2]]);
+            let nlen = u16::from_le_bytes([zlib[at + 3], zlib[at + 4]]);
+            assert_eq!(len, !nlen);
+            at += 5;
+            data.extend_from_slice(&zlib[at..at + len as usize]);
+            at += len as usize;
+            if last {
+                break;
+            }
+        }
+
+        assert_eq!(&zlib[at..], &adler32(&data).to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn checksums_match_known_answers() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(b""), 1);
+    }
+
+    #[test]
+    fn encoded_image_has_the_png_layout() {
+        let rgba = (0..16).collect::<Vec<u8>>();
+        let png = encode(2, 2, &rgba);
+
+        assert_eq!(&png[..8], &SIGNATURE);
+        assert_eq!(&png[8..16], &[0, 0, 0, 13, b'I', b'H', b'D', b'R']);
+        assert_eq!(&png[16..20], &2_u32.to_be_bytes());
+        assert_eq!(&png[20..24], &2_u32.to_be_bytes());
+        assert_eq!(&png[24..29], &[8, 6, 0, 0, 0]);
+        assert_eq!(&png[29..33], &crc32(&png[12..29]).to_be_bytes());
+
+        let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let idat = &png[41..41 + idat_len];
+        let scanlines = [&[0], &rgba[..8], &[0], &rgba[8..]].concat();
+        assert_eq!(inflate_stored(idat), scanlines);
+
+        let iend = &png[41 + idat_len + 4..];
+        assert_eq!(
+            iend,
+            &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn stored_deflate_splits_long_data_into_blocks() {
+        let data = (0..2 * MAX_STORED_BLOCK + 10)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let zlib = zlib_stored(&data);
+        assert_eq!(zlib.len(), 2 + 3 * 5 + data.len() + 4);
+        assert_eq!(inflate_stored(&zlib), data);
+
+        assert_eq!(inflate_stored(&zlib_stored(&[])), Vec::<u8>::new());
+    }
+}
@@ -1,14 +1,106 @@
-#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
 pub enum SpatialScheme {
     Central,
     Upwind,
     LaxWendroff,
+    BeamWarming,
+    Fromm,
     ENO,
     WENO,
+    WenoZ,
+    MUSCL,
+    QUICK,
     CIP,
+    RationalCIP,
+    SemiLagrangian,
+    FluxLimited,
+    Godunov,
+    Rusanov,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, Debug)]
+impl SpatialScheme {
+    pub fn description(&self) -> &'static str {
+        match self {
+            SpatialScheme::Central => {
+                "2nd order, non-dissipative and dispersive; unstable with Euler, needs RK3+"
+            }
+            SpatialScheme::Upwind => "1st order, strongly dissipative, monotone; CFL <= 1",
+            SpatialScheme::LaxWendroff => {
+                "2nd order, dispersive with trailing oscillations; CFL <= 1"
+            }
+            SpatialScheme::BeamWarming => "2nd order upwind-biased, leading oscillations; CFL <= 2",
+            SpatialScheme::Fromm => "2nd order, average of LW and BW with low dispersion; CFL <= 1",
+            SpatialScheme::ENO => "3rd order, picks the smoothest stencil; needs RK2+",
+            SpatialScheme::WENO => {
+                "5th order, nonlinear stencil weights, non-oscillatory; pair with (TVD) RK3"
+            }
+            SpatialScheme::WenoZ => "5th order, WENO with sharper weights at critical points",
+            SpatialScheme::MUSCL => "2nd order TVD slope-limited finite volume; CFL <= 0.5",
+            SpatialScheme::QUICK => {
+                "3rd order upwind-biased finite volume, mild oscillations; needs RK2+"
+            }
+            SpatialScheme::CIP => {
+                "3rd order cubic Hermite transport of u and du/dx, low dispersion; CFL <= 1"
+            }
+            SpatialScheme::RationalCIP => {
+                "CIP with a rational interpolant, suppresses overshoot at jumps; CFL <= 1"
+            }
+            SpatialScheme::SemiLagrangian => {
+                "3rd order cubic backtracking, dissipative; unconditionally stable"
+            }
+            SpatialScheme::FluxLimited => {
+                "2nd order TVD blend of upwind and Lax-Wendroff fluxes; CFL <= 1"
+            }
+            SpatialScheme::Godunov => {
+                "1st order exact Riemann flux finite volume, captures shocks; CFL <= 1"
+            }
+            SpatialScheme::Rusanov => {
+                "1st order local Lax-Friedrichs flux, more dissipative than Godunov; CFL <= 1"
+            }
+        }
+    }
+
+    pub fn stencil_radius(&self) -> usize {
+        match self {
+            SpatialScheme::Upwind
+            | SpatialScheme::LaxWendroff
+            | SpatialScheme::CIP
+            | SpatialScheme::RationalCIP
+            | SpatialScheme::Godunov
+            | SpatialScheme::Rusanov => 1,
+            SpatialScheme::BeamWarming
+            | SpatialScheme::Fromm
+            | SpatialScheme::MUSCL
+            | SpatialScheme::QUICK
+            | SpatialScheme::SemiLagrangian
+            | SpatialScheme::FluxLimited => 2,
+            SpatialScheme::Central
+            | SpatialScheme::ENO
+            | SpatialScheme::WENO
+            | SpatialScheme::WenoZ => 3,
+        }
+    }
+
+    pub fn relative_cost(&self) -> usize {
+        match self {
+            SpatialScheme::Central | SpatialScheme::Upwind => 1,
+            SpatialScheme::LaxWendroff
+            | SpatialScheme::BeamWarming
+            | SpatialScheme::Godunov
+            | SpatialScheme::Rusanov => 2,
+            SpatialScheme::QUICK | SpatialScheme::SemiLagrangian => 3,
+            SpatialScheme::Fromm
+            | SpatialScheme::MUSCL
+            | SpatialScheme::CIP
+            | SpatialScheme::FluxLimited => 4,
+            SpatialScheme::RationalCIP | SpatialScheme::ENO => 6,
+            SpatialScheme::WENO | SpatialScheme::WenoZ => 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
 pub enum TemporalScheme {
     ForwardEuler,
     Rk2,
@@ -17,44 +109,411 @@ pub enum TemporalScheme {
     TvdRk2,
     TvdRk3,
     TvdRk4,
+    AdamsBashforth2,
+    BackwardEuler,
+    CrankNicolson,
+    Rk45,
+}
+
+impl TemporalScheme {
+    pub fn stages(&self) -> usize {
+        match self {
+            TemporalScheme::ForwardEuler
+            | TemporalScheme::AdamsBashforth2
+            | TemporalScheme::BackwardEuler
+            | TemporalScheme::CrankNicolson => 1,
+            TemporalScheme::Rk2 | TemporalScheme::TvdRk2 => 2,
+            TemporalScheme::Rk3 | TemporalScheme::TvdRk3 => 3,
+            TemporalScheme::Rk4 | TemporalScheme::TvdRk4 => 4,
+            TemporalScheme::Rk45 => 7,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TemporalScheme::ForwardEuler => "1st order explicit; unstable for central-type schemes",
+            TemporalScheme::Rk2 => "2nd order explicit Runge-Kutta (midpoint)",
+            TemporalScheme::Rk3 => {
+                "3rd order explicit Runge-Kutta, covers part of the imaginary axis"
+            }
+            TemporalScheme::Rk4 => "4th order classical Runge-Kutta, largest stability region here",
+            TemporalScheme::TvdRk2 => "2nd order strong-stability-preserving Runge-Kutta (Heun)",
+            TemporalScheme::TvdRk3 => {
+                "3rd order strong-stability-preserving Runge-Kutta (Shu-Osher)"
+            }
+            TemporalScheme::TvdRk4 => "4th order strong-stability-preserving Runge-Kutta",
+            TemporalScheme::AdamsBashforth2 => {
+                "2nd order explicit multistep, one evaluation per step, small stability region"
+            }
+            TemporalScheme::BackwardEuler => {
+                "1st order implicit, unconditionally stable and strongly dissipative"
+            }
+            TemporalScheme::Rk45 => {
+                "5th order Dormand-Prince with an embedded 4th order estimate that adapts dt"
+            }
+            TemporalScheme::CrankNicolson => {
+                "2nd order implicit, unconditionally stable and non-dissipative"
+            }
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum Limiter {
+    Minmod,
+    Superbee,
+    VanLeer,
+    MC,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum BoundaryCondition {
+    Periodic,
+    ZeroGradient,
+    Dirichlet,
+    Reflective,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum InitialCondition {
+    Square,
+    Gaussian,
+    Sine,
+    Triangle,
+    Step,
+    Noise,
+    TwoGaussians,
+    Expression,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum Dimension {
+    One,
+    Two,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum VelocityField {
+    Constant,
+    Sine,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum Form {
+    Conservation,
+    Advection,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum Equation {
+    LinearAdvection,
+    Burgers,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum GridMode {
+    Spacing,
+    Cells,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, Debug)]
+pub enum PlotStyle {
+    Points,
+    Line,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Formulation {
+    FiniteDifference,
+    FiniteVolume,
 }
 
 #[derive(Clone, Debug)]
 pub struct Descriptor {
-    pub time_scale: f64,
+    pub playback_speed: f64,
     pub delta_t: f64,
     pub delta_x: f64,
     pub bound: f64,
     pub x_1: f64,
     pub x_2: f64,
     pub vel: f64,
+    pub vel_y: f64,
+    pub velocity_field: VelocityField,
+    pub vel_freq: f64,
+    pub form: Form,
+    pub dimension: Dimension,
+    pub initial_condition: InitialCondition,
+    pub equation: Equation,
     pub spatial_scheme: SpatialScheme,
     pub temporal_scheme: TemporalScheme,
+    pub limiter: Limiter,
+    pub boundary_condition: BoundaryCondition,
+    pub plot_style: PlotStyle,
+    pub show_limiter: bool,
+    pub show_weights: bool,
+    pub stretching: f64,
+    pub fct: bool,
+    pub break_on_shock: bool,
+    pub show_gradient: bool,
+    pub show_initial: bool,
+    pub central_order: usize,
+    pub adaptive: bool,
+    pub target_cfl: f64,
+    pub tolerance: f64,
+    pub record_history: bool,
+    pub history_length: usize,
+    pub diagnostic_stride: usize,
+    pub amplitude: f64,
+    pub sigma: f64,
+    pub grid_mode: GridMode,
+    pub cells: usize,
+    pub autoscale_y: bool,
+    pub y_min: f64,
+    pub y_max: f64,
+    pub t_final: Option<f64>,
+    pub seed: u64,
+    pub expression: crate::expression::Expression,
+    pub center_1: f64,
+    pub amplitude_1: f64,
+    pub center_2: f64,
+    pub amplitude_2: f64,
+    pub clamp_bounds: Option<(f64, f64)>,
+}
+
+impl Default for Descriptor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Descriptor {
     pub fn new() -> Self {
         Self {
-            time_scale: 1.0,
+            playback_speed: 1.0,
             delta_t: 0.01666,
             delta_x: 0.05,
             bound: 10.0,
             x_1: 2.0,
             x_2: 4.0,
             vel: 1.0,
+            vel_y: 0.5,
+            velocity_field: VelocityField::Constant,
+            vel_freq: 0.6283,
+            form: Form::Conservation,
+            dimension: Dimension::One,
+            initial_condition: InitialCondition::Square,
+            equation: Equation::LinearAdvection,
             spatial_scheme: SpatialScheme::WENO,
             temporal_scheme: TemporalScheme::ForwardEuler,
+            limiter: Limiter::Minmod,
+            boundary_condition: BoundaryCondition::Periodic,
+            plot_style: PlotStyle::Points,
+            show_limiter: false,
+            show_weights: false,
+            stretching: 0.0,
+            fct: false,
+            break_on_shock: false,
+            show_gradient: false,
+            show_initial: false,
+            central_order: 2,
+            adaptive: false,
+            target_cfl: 0.5,
+            tolerance: 1e-6,
+            record_history: false,
+            history_length: 500,
+            diagnostic_stride: 10,
+            amplitude: 1.0,
+            sigma: 0.333,
+            grid_mode: GridMode::Spacing,
+            cells: 200,
+            autoscale_y: true,
+            y_min: -0.5,
+            y_max: 1.5,
+            t_final: None,
+            seed: 0,
+            expression: crate::expression::Expression::new("exp(-(x - 5)^2)"),
+            center_1: 3.0,
+            amplitude_1: 1.0,
+            center_2: 6.0,
+            amplitude_2: 0.5,
+            clamp_bounds: None,
         }
     }
 
     pub fn show_inside(&mut self, ui: &mut egui::Ui) {
-        ui.add(egui::Slider::new(&mut self.time_scale, 0.0..=10.0).text("Time Scale"));
-        ui.add(egui::Slider::new(&mut self.delta_t, 0.0..=0.1).text("Delta Time"));
-        ui.add(egui::Slider::new(&mut self.delta_x, 0.0..=0.1).text("Delta Space"));
+        ui.add(egui::Slider::new(&mut self.playback_speed, 0.0..=10.0).text("Playback Speed"))
+            .on_hover_text("Simulated seconds per wall-clock second; higher is faster, 0 pauses");
+        ui.add(egui::Slider::new(&mut self.delta_t, MIN_DELTA_T..=0.1).text("Delta Time"));
+        ui.checkbox(&mut self.adaptive, "Adaptive Time Step");
+        if self.adaptive {
+            ui.add(egui::Slider::new(&mut self.target_cfl, 0.0..=2.0).text("Target CFL"));
+        }
+
+        let mut stop = self.t_final.is_some();
+        ui.checkbox(&mut stop, "Stop at Final Time");
+        self.t_final = match (stop, self.t_final) {
+            (true, None) => Some(10.0),
+            (false, _) => None,
+            (true, t_final) => t_final,
+        };
+        if let Some(t_final) = &mut self.t_final {
+            ui.add(egui::Slider::new(t_final, 0.0..=100.0).text("Final Time"));
+        }
         ui.add(egui::Slider::new(&mut self.bound, 0.0..=100.0).text("Bound"));
-        ui.add(egui::Slider::new(&mut self.x_1, 0.0..=10.0).text("x1"));
-        ui.add(egui::Slider::new(&mut self.x_2, 0.0..=10.0).text("x2"));
-        ui.add(egui::Slider::new(&mut self.vel, 0.0..=10.0).text("Velocity"));
+        ui.add(egui::Slider::new(&mut self.stretching, 0.0..=5.0).text("Grid Stretching"))
+            .on_hover_text("Cluster nodes toward x = 0 along x = L sinh(s xi) / sinh(s)");
+
+        let mut clamp = self.clamp_bounds.is_some();
+        ui.checkbox(&mut clamp, "Clamp Values")
+            .on_hover_text("Clip u into [min, max] after every step");
+        self.clamp_bounds = match (clamp, self.clamp_bounds) {
+            (true, None) => Some((0.0, self.amplitude)),
+            (false, _) => None,
+            (true, bounds) => bounds,
+        };
+        if let Some((min, max)) = &mut self.clamp_bounds {
+            ui.add(egui::Slider::new(min, -5.0..=5.0).text("Clamp Min"));
+            ui.add(egui::Slider::new(max, -5.0..=5.0).text("Clamp Max"));
+        }
+
+        let display = format!("{:?}", self.grid_mode);
+        egui::ComboBox::from_label("Grid")
+            .selected_text(display)
+            .show_ui(ui, |ui| {
+                <GridMode as strum::IntoEnumIterator>::iter().for_each(|mode| {
+                    let display = format!("{:?}", mode);
+                    ui.selectable_value(&mut self.grid_mode, mode, display);
+                });
+            });
+
+        match self.grid_mode {
+            GridMode::Spacing => {
+                ui.add(egui::Slider::new(&mut self.delta_x, MIN_DELTA_X..=0.1).text("Delta Space"));
+                self.cells = discretize(self.bound, self);
+            }
+            GridMode::Cells => {
+                ui.add(egui::Slider::new(&mut self.cells, MIN_CELLS..=10000).text("Cells"));
+                self.delta_x = self.bound / self.cells as f64;
+            }
+        }
+        ui.add(egui::Slider::new(&mut self.x_1, 0.0..=self.bound).text("x1"));
+        ui.add(egui::Slider::new(&mut self.x_2, 0.0..=self.bound).text("x2"));
+        self.x_1 = self.x_1.min(self.bound);
+        self.x_2 = self.x_2.min(self.bound);
+        if uses_interval(self.initial_condition) && self.x_2 <= self.x_1 {
+            ui.label(
+                egui::RichText::new("x1 must be below x2 or the pulse is empty")
+                    .color(egui::Color32::YELLOW),
+            );
+        }
+        ui.add(egui::Slider::new(&mut self.vel, -10.0..=10.0).text("Velocity"));
+        if self.dimension == Dimension::Two {
+            ui.add(egui::Slider::new(&mut self.vel_y, -10.0..=10.0).text("Velocity y"));
+        }
+        let direction = match self.equation {
+            Equation::Burgers => "follows sign(u)",
+            Equation::LinearAdvection if 0.0 < self.vel => "\u{2192} (upwind is left)",
+            Equation::LinearAdvection if self.vel < 0.0 => "\u{2190} (upwind is right)",
+            Equation::LinearAdvection => "none",
+        };
+        ui.label(format!("Transport {}", direction));
+
+        let display = format!("{:?}", self.velocity_field);
+        egui::ComboBox::from_label("Velocity Field")
+            .selected_text(display)
+            .show_ui(ui, |ui| {
+                <VelocityField as strum::IntoEnumIterator>::iter().for_each(|field| {
+                    let display = format!("{:?}", field);
+                    ui.selectable_value(&mut self.velocity_field, field, display);
+                });
+            });
+
+        if self.velocity_field == VelocityField::Sine {
+            ui.add(egui::Slider::new(&mut self.vel_freq, 0.0..=5.0).text("Velocity Frequency"));
+
+            let display = format!("{:?}", self.form);
+            egui::ComboBox::from_label("Form")
+                .selected_text(display)
+                .show_ui(ui, |ui| {
+                    <Form as strum::IntoEnumIterator>::iter().for_each(|form| {
+                        let display = format!("{:?}", form);
+                        ui.selectable_value(&mut self.form, form, display);
+                    });
+                })
+                .response
+                .on_hover_text(
+                    "Conservation: du/dt + d(vu)/dx = 0, Advection: du/dt + v du/dx = 0",
+                );
+        }
+
+        let display = format!("{:?}", self.dimension);
+        egui::ComboBox::from_label("Dimension")
+            .selected_text(display)
+            .show_ui(ui, |ui| {
+                <Dimension as strum::IntoEnumIterator>::iter().for_each(|dimension| {
+                    let display = format!("{:?}", dimension);
+                    ui.selectable_value(&mut self.dimension, dimension, display);
+                });
+            });
+
+        let display = format!("{:?}", self.initial_condition);
+        egui::ComboBox::from_label("Initial Condition")
+            .selected_text(display)
+            .show_ui(ui, |ui| {
+                <InitialCondition as strum::IntoEnumIterator>::iter().for_each(|condition| {
+                    let display = format!("{:?}", condition);
+                    ui.selectable_value(&mut self.initial_condition, condition, display);
+                });
+            });
+
+        if self.initial_condition == InitialCondition::Square {
+            ui.add(egui::Slider::new(&mut self.amplitude, 0.0..=10.0).text("Amplitude"));
+        }
+        if self.initial_condition == InitialCondition::Gaussian {
+            ui.add(egui::Slider::new(&mut self.amplitude, 0.0..=10.0).text("Amplitude"));
+            ui.add(egui::Slider::new(&mut self.sigma, 0.0..=5.0).text("Sigma"));
+        }
+        if self.initial_condition == InitialCondition::TwoGaussians {
+            ui.add(egui::Slider::new(&mut self.center_1, 0.0..=10.0).text("Center 1"));
+            ui.add(egui::Slider::new(&mut self.amplitude_1, 0.0..=10.0).text("Amplitude 1"));
+            ui.add(egui::Slider::new(&mut self.center_2, 0.0..=10.0).text("Center 2"));
+            ui.add(egui::Slider::new(&mut self.amplitude_2, 0.0..=10.0).text("Amplitude 2"));
+            ui.add(egui::Slider::new(&mut self.sigma, 0.0..=5.0).text("Sigma"));
+        }
+        if self.initial_condition == InitialCondition::Noise {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.seed));
+                ui.label("Seed");
+            });
+        }
+        if self.initial_condition == InitialCondition::Expression {
+            ui.horizontal(|ui| {
+                let mut source = self.expression.source().to_string();
+                if ui.text_edit_singleline(&mut source).changed() {
+                    self.expression = crate::expression::Expression::new(&source);
+                }
+                ui.label("u0(x)");
+            });
+            if let Some(e) = self.expression.error() {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+
+        let display = format!("{:?}", self.equation);
+        egui::ComboBox::from_label("Equation")
+            .selected_text(display)
+            .show_ui(ui, |ui| {
+                <Equation as strum::IntoEnumIterator>::iter().for_each(|equation| {
+                    let display = format!("{:?}", equation);
+                    ui.selectable_value(&mut self.equation, equation, display);
+                });
+            });
+
+        if self.equation == Equation::Burgers && self.dimension == Dimension::One {
+            ui.checkbox(&mut self.break_on_shock, "Break on Shock")
+                .on_hover_text("Pause when a single cell first holds a quarter of the range of u");
+        }
 
         let display = format!("{:?}", self.spatial_scheme);
         egui::ComboBox::from_label("Spatial Scheme")
@@ -62,9 +521,60 @@ impl Descriptor {
             .show_ui(ui, |ui| {
                 <SpatialScheme as strum::IntoEnumIterator>::iter().for_each(|scheme| {
                     let display = format!("{:?}", scheme);
-                    ui.selectable_value(&mut self.spatial_scheme, scheme, display);
+                    ui.selectable_value(&mut self.spatial_scheme, scheme, display)
+                        .on_hover_text(scheme.description());
                 });
             });
+        ui.label(format!(
+            "Formulation {:?}",
+            formulation(self.spatial_scheme)
+        ));
+
+        if self.spatial_scheme == SpatialScheme::Central {
+            ui.add(
+                egui::Slider::new(&mut self.central_order, 2..=6)
+                    .step_by(2.0)
+                    .text("Order"),
+            );
+        }
+
+        if matches!(
+            self.spatial_scheme,
+            SpatialScheme::MUSCL | SpatialScheme::FluxLimited
+        ) {
+            let display = format!("{:?}", self.limiter);
+            egui::ComboBox::from_label("Limiter")
+                .selected_text(display)
+                .show_ui(ui, |ui| {
+                    <Limiter as strum::IntoEnumIterator>::iter().for_each(|limiter| {
+                        let display = format!("{:?}", limiter);
+                        ui.selectable_value(&mut self.limiter, limiter, display);
+                    });
+                });
+
+            ui.checkbox(&mut self.show_limiter, "Show Limiter Activity");
+        }
+
+        if matches!(
+            self.spatial_scheme,
+            SpatialScheme::WENO | SpatialScheme::WenoZ
+        ) && self.dimension == Dimension::One
+        {
+            ui.checkbox(&mut self.show_weights, "Show WENO Weights")
+                .on_hover_text("Nonlinear weights of the three candidate stencils per cell");
+        }
+
+        if self.spatial_scheme == SpatialScheme::Upwind && self.dimension == Dimension::One {
+            ui.checkbox(&mut self.fct, "Flux-Corrected Transport")
+                .on_hover_text("Add back Lax-Wendroff antidiffusion limited by Zalesak's limiter");
+        }
+
+        ui.checkbox(&mut self.record_history, "Record History");
+        if self.record_history {
+            ui.add(egui::Slider::new(&mut self.history_length, 1..=5000).text("History Length"));
+        }
+        ui.add(egui::Slider::new(&mut self.diagnostic_stride, 1..=100).text("Diagnostic Stride"))
+            .on_hover_text("Log the diagnostics every n-th step");
 
         let display = format!("{:?}", self.temporal_scheme);
         egui::ComboBox::from_label("Temporal Scheme")
@@ -72,12 +582,158 @@ impl Descriptor {
             .show_ui(ui, |ui| {
                 <TemporalScheme as strum::IntoEnumIterator>::iter().for_each(|scheme| {
                     let display = format!("{:?}", scheme);
-                    ui.selectable_value(&mut self.temporal_scheme, scheme, display);
+                    ui.selectable_value(&mut self.temporal_scheme, scheme, display)
+                        .on_hover_text(scheme.description());
+                });
+            });
+        if self.temporal_scheme == TemporalScheme::Rk45 {
+            ui.add(
+                egui::Slider::new(&mut self.tolerance, 1e-10..=1e-2)
+                    .logarithmic(true)
+                    .text("Tolerance"),
+            )
+            .on_hover_text("Local error allowed per step, relative to 1 + |u|");
+        }
+
+        let display = format!("{:?}", self.boundary_condition);
+        egui::ComboBox::from_label("Boundary Condition")
+            .selected_text(display)
+            .show_ui(ui, |ui| {
+                <BoundaryCondition as strum::IntoEnumIterator>::iter().for_each(|condition| {
+                    let display = format!("{:?}", condition);
+                    ui.selectable_value(&mut self.boundary_condition, condition, display);
                 });
             });
+
+        let display = format!("{:?}", self.plot_style);
+        egui::ComboBox::from_label("Plot Style")
+            .selected_text(display)
+            .show_ui(ui, |ui| {
+                <PlotStyle as strum::IntoEnumIterator>::iter().for_each(|style| {
+                    let display = format!("{:?}", style);
+                    ui.selectable_value(&mut self.plot_style, style, display);
+                });
+            });
+        if self.dimension == Dimension::One {
+            ui.checkbox(&mut self.show_gradient, "Show Gradient")
+                .on_hover_text(
+                    "CIP schemes plot their transported g, other schemes a central difference of u",
+                );
+            ui.checkbox(&mut self.show_initial, "Show Initial Condition");
+        }
+        ui.checkbox(&mut self.autoscale_y, "Autoscale Y");
+        if !self.autoscale_y {
+            ui.add(egui::Slider::new(&mut self.y_min, -5.0..=5.0).text("y min"));
+            ui.add(egui::Slider::new(&mut self.y_max, -5.0..=5.0).text("y max"));
+        }
+
+        let cfl = self.cfl();
+        let max_cfl = self.max_cfl();
+        let text = if cfl <= max_cfl {
+            egui::RichText::new(format!("CFL {:.2} <= {:.2}", cfl, max_cfl))
+                .color(egui::Color32::GREEN)
+        } else {
+            egui::RichText::new(format!("CFL {:.2} > {:.2} (unstable)", cfl, max_cfl))
+                .color(egui::Color32::RED)
+        };
+        ui.label(text);
+        ui.label(format!(
+            "Ops/step {} ({} stages x {} per cell)",
+            self.ops_per_step(),
+            self.stages(),
+            self.spatial_scheme.relative_cost()
+        ))
+        .on_hover_text("Spatial operator evaluations per step, in upwind-difference units");
+    }
+
+    pub fn stages(&self) -> usize {
+        match self.spatial_scheme {
+            SpatialScheme::SemiLagrangian | SpatialScheme::CIP | SpatialScheme::RationalCIP => 1,
+            _ => self.temporal_scheme.stages(),
+        }
+    }
+
+    pub fn ops_per_step(&self) -> usize {
+        let n = discretize(self.bound, self);
+        let cells = match self.dimension {
+            Dimension::One => n,
+            Dimension::Two => 2 * n * n,
+        };
+        self.stages() * self.spatial_scheme.relative_cost() * cells
+    }
+
+    pub fn max_cfl(&self) -> f64 {
+        let limit = max_cfl(self.spatial_scheme, self.temporal_scheme);
+        match (self.spatial_scheme, self.central_order) {
+            (SpatialScheme::Central, 4) => limit / 1.372,
+            (SpatialScheme::Central, 6) => limit / 1.586,
+            _ => limit,
+        }
+    }
+
+    pub fn cfl(&self) -> f64 {
+        let vel = match self.dimension {
+            Dimension::One => self.vel.abs(),
+            Dimension::Two => self.vel.abs() + self.vel_y.abs(),
+        };
+        if self.adaptive {
+            self.target_cfl
+        } else {
+            vel * self.delta_t / self.min_spacing()
+        }
+    }
+
+    pub fn min_spacing(&self) -> f64 {
+        if self.stretching == 0.0 {
+            return self.delta_x;
+        }
+
+        (0..discretize(self.bound, self) as isize)
+            .map(|i| node_position(i + 1, self) - node_position(i, self))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+pub fn formulation(spatial_scheme: SpatialScheme) -> Formulation {
+    match spatial_scheme {
+        SpatialScheme::MUSCL
+        | SpatialScheme::QUICK
+        | SpatialScheme::FluxLimited
+        | SpatialScheme::Godunov
+        | SpatialScheme::Rusanov => Formulation::FiniteVolume,
+        _ => Formulation::FiniteDifference,
+    }
+}
+
+pub fn max_cfl(spatial_scheme: SpatialScheme, temporal_scheme: TemporalScheme) -> f64 {
+    let limits = match spatial_scheme {
+        SpatialScheme::Central => [0.0, 0.0, 1.73, 2.82, 0.0, 1.0],
+        SpatialScheme::Upwind => [1.0, 1.0, 1.25, 1.39, 0.5, 1.65],
+        SpatialScheme::LaxWendroff => [1.0, 1.0, 1.12, 1.18, 0.7, 0.0],
+        SpatialScheme::BeamWarming => [2.0, 2.0, 2.0, 2.0, 0.29, 0.0],
+        SpatialScheme::Fromm => [1.0, 1.0, 1.26, 1.39, 0.5, 0.0],
+        SpatialScheme::ENO => [0.0, 0.87, 1.62, 1.74, 0.58, 2.07],
+        SpatialScheme::WENO | SpatialScheme::WenoZ => [0.0, 0.12, 1.43, 1.73, 0.1, 2.02],
+        SpatialScheme::MUSCL => [0.5; 6],
+        SpatialScheme::QUICK => [0.0, 0.79, 1.85, 2.02, 0.58, 2.34],
+        SpatialScheme::CIP | SpatialScheme::RationalCIP => [1.0; 6],
+        SpatialScheme::SemiLagrangian => [f64::INFINITY; 6],
+        SpatialScheme::FluxLimited => [1.0, 1.0, 1.0, 1.0, 0.7, 0.0],
+        SpatialScheme::Godunov | SpatialScheme::Rusanov => [1.0, 1.0, 1.25, 1.39, 0.5, 1.65],
+    };
+
+    match temporal_scheme {
+        TemporalScheme::BackwardEuler | TemporalScheme::CrankNicolson => f64::INFINITY,
+        TemporalScheme::ForwardEuler => limits[0],
+        TemporalScheme::Rk2 | TemporalScheme::TvdRk2 => limits[1],
+        TemporalScheme::Rk3 | TemporalScheme::TvdRk3 => limits[2],
+        TemporalScheme::Rk4 | TemporalScheme::TvdRk4 => limits[3],
+        TemporalScheme::AdamsBashforth2 => limits[4],
+        TemporalScheme::Rk45 => limits[5],
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Debug)]
 pub enum Buffer {
     Base {
@@ -87,364 +743,3892 @@ pub enum Buffer {
         u: nalgebra::DVector<f64>,
         g: nalgebra::DVector<f64>,
     },
+    Plane {
+        u: nalgebra::DMatrix<f64>,
+    },
+    Unfolded {
+        u: nalgebra::DVector<f64>,
+        unfolded: Box<Buffer>,
+    },
 }
 
-#[derive(Clone, Debug)]
-pub struct Scenario {
-    pub desc: Descriptor,
-    buffer: Buffer,
-}
-
-impl Scenario {
-    pub fn new(desc: Descriptor) -> Self {
-        let buffer = match desc.spatial_scheme {
-            SpatialScheme::CIP => {
-                let n = discretize(desc.bound, &desc);
-                let mut u = nalgebra::DVector::zeros(n);
-
-                u = init_square_wave(&u, &desc);
+impl Buffer {
+    fn new(u: nalgebra::DVector<f64>, desc: &Descriptor) -> Self {
+        if desc.boundary_condition == BoundaryCondition::Reflective {
+            let unfolded = Buffer::new(unfold(&u), &unfolded_desc(desc));
+            return Buffer::Unfolded {
+                u,
+                unfolded: Box::new(unfolded),
+            };
+        }
 
-                let g = nalgebra::DVector::zeros(n);
+        match desc.spatial_scheme {
+            SpatialScheme::CIP | SpatialScheme::RationalCIP => {
+                let g = gradient(&u, desc);
                 Buffer::CIP { u, g }
             }
-            _ => {
-                let n = discretize(desc.bound, &desc);
-                let mut u = nalgebra::DVector::zeros(n);
-
-                u = init_square_wave(&u, &desc);
-
-                Buffer::Base { u }
-            }
-        };
-
-        Self { desc, buffer }
+            _ => Buffer::Base { u },
+        }
     }
 
-    pub fn forward(&mut self) {
-        match (self.desc.spatial_scheme, &mut self.buffer) {
-            (
-                SpatialScheme::Central
-                | SpatialScheme::Upwind
-                | SpatialScheme::LaxWendroff
-                | SpatialScheme::ENO
-                | SpatialScheme::WENO,
-                Buffer::Base { u },
-            ) => {
-                let diff_fn = match self.desc.spatial_scheme {
-                    SpatialScheme::Central => central_diff,
-                    SpatialScheme::Upwind => upwind_diff,
-                    SpatialScheme::LaxWendroff => lax_wendroff_diff,
-                    SpatialScheme::ENO => eno_diff,
-                    SpatialScheme::WENO => weno_diff,
-                    _ => unreachable!(),
-                };
-
-                let forward_fn = match self.desc.temporal_scheme {
-                    TemporalScheme::ForwardEuler => forward_euler,
-                    TemporalScheme::Rk2 => rk2,
-                    TemporalScheme::Rk3 => rk3,
-                    TemporalScheme::Rk4 => rk4,
-                    TemporalScheme::TvdRk2 => tvd_rk2,
-                    TemporalScheme::TvdRk3 => tvd_rk3,
-                    TemporalScheme::TvdRk4 => tvd_rk4,
-                };
-
-                *u = forward_fn(u, diff_fn, &self.desc);
-            }
-            (SpatialScheme::CIP, Buffer::CIP { u, g }) => {
-                (*u, *g) = cip(u, g, &self.desc);
+    fn clamp(&mut self, min: f64, max: f64) {
+        match self {
+            Buffer::Base { u } | Buffer::CIP { u, .. } => u.apply(|u| *u = u.clamp(min, max)),
+            Buffer::Plane { u } => u.apply(|u| *u = u.clamp(min, max)),
+            Buffer::Unfolded { u, unfolded } => {
+                unfolded.clamp(min, max);
+                u.apply(|u| *u = u.clamp(min, max));
             }
-            _ => unreachable!(),
         }
     }
+}
 
-    pub fn show_inside(&mut self, ui: &mut egui_plot::PlotUi) {
-        let u = match &self.buffer {
-            Buffer::Base { u } => u,
-            Buffer::CIP { u, .. } => u,
-        };
+#[derive(Clone, Debug)]
+pub struct TimeSeries {
+    points: std::collections::VecDeque<[f64; 2]>,
+    capacity: usize,
+}
 
-        let points = u
-            .iter()
-            .enumerate()
-            .map(|(i, y)| [i as f64 * self.desc.delta_x, *y])
-            .collect::<Vec<_>>();
+impl TimeSeries {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            points: std::collections::VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
 
-        let points = egui_plot::Points::new(points)
-            .radius(4.0)
-            .color(egui::Color32::RED);
+    pub fn push(&mut self, t: f64, value: f64) {
+        if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back([t, value]);
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
 
-        ui.add(points);
+    pub fn points(&self) -> Vec<[f64; 2]> {
+        self.points.iter().copied().collect()
     }
 }
 
-fn discretize(x: f64, desc: &Descriptor) -> usize {
-    (x / desc.delta_x).round() as usize
+#[derive(Clone, Copy, Debug)]
+pub struct DiagnosticSample {
+    pub t: f64,
+    pub mass: f64,
+    pub total_variation: f64,
+    pub energy: f64,
+    pub l_2: f64,
 }
 
-fn init_square_wave(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    let n = u.len();
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ErrorSample {
+    pub t: f64,
+    pub l_1: f64,
+    pub l_2: f64,
+    pub l_inf: f64,
+    pub l_2_quad: f64,
+    pub phase: f64,
+}
 
-    let mut ret = nalgebra::DVector::zeros(n);
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticLog {
+    samples: std::collections::VecDeque<DiagnosticSample>,
+    steps: usize,
+}
 
-    let lower = discretize(desc.x_1, desc);
-    let upper = discretize(desc.x_2, desc);
-    for i in lower..upper {
-        ret[i] = 1.0;
+impl DiagnosticLog {
+    pub fn due(&mut self, stride: usize) -> bool {
+        let due = self.steps.is_multiple_of(stride.max(1));
+        self.steps += 1;
+        due
     }
 
-    ret
-}
-
-fn forward_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    let n = u.len();
-    let dx = desc.delta_x;
-    let p = -desc.vel * desc.delta_t;
+    pub fn push(&mut self, sample: DiagnosticSample) {
+        if self.samples.len() == HISTORY_CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
 
-    let mut ret = nalgebra::DVector::zeros(n);
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.steps = 0;
+    }
 
-    for i in 0..n - 1 {
-        ret[i] = (u[i + 1] - u[i]) / dx * p;
+    pub fn len(&self) -> usize {
+        self.samples.len()
     }
 
-    ret
-}
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
 
-fn backward_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    let n = u.len();
-    let dx = desc.delta_x;
-    let p = -desc.vel * desc.delta_t;
+    pub fn to_json(&self) -> String {
+        let number = |value: f64| {
+            if value.is_finite() {
+                format!("{}", value)
+            } else {
+                "null".to_string()
+            }
+        };
 
-    let mut ret = nalgebra::DVector::zeros(n);
+        let samples = self
+            .samples
+            .iter()
+            .map(|sample| {
+                format!(
+                    "  {{\"t\": {}, \"mass\": {}, \"total_variation\": {}, \"energy\": {}, \"l2_error\": {}}}",
+                    number(sample.t),
+                    number(sample.mass),
+                    number(sample.total_variation),
+                    number(sample.energy),
+                    number(sample.l_2)
+                )
+            })
+            .collect::<Vec<_>>();
 
-    for i in 1..n {
-        ret[i] = (u[i] - u[i - 1]) / dx * p;
+        format!("[\n{}\n]\n", samples.join(",\n"))
     }
-
-    ret
 }
 
-fn central_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    let n = u.len();
-    let dx = desc.delta_x;
-    let p = -desc.vel * desc.delta_t;
-
-    let mut ret = nalgebra::DVector::zeros(n);
-
-    for i in 1..u.len() - 1 {
-        let grad_1 = (u[i + 1] - u[i - 1]) / (2.0 * dx);
-        ret[i] = grad_1 * p;
-    }
+const HISTORY_CAPACITY: usize = 10000;
+const SHOCK_JUMP_FRACTION: f64 = 0.25;
+const SPACE_TIME_ROWS: usize = 512;
 
-    ret
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub t: f64,
+    pub values: Vec<f64>,
 }
 
-fn upwind_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    if 0.0 <= desc.vel {
-        backward_diff(u, desc)
-    } else {
-        forward_diff(u, desc)
-    }
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    pub desc: Descriptor,
+    pub t: f64,
+    pub dt: f64,
+    pub diverged: Option<f64>,
+    pub shock: Option<f64>,
+    pub color: egui::Color32,
+    pub temporal_comparison: bool,
+    reversed: bool,
+    pub total_variation_history: TimeSeries,
+    pub peak_history: TimeSeries,
+    pub energy_history: TimeSeries,
+    pub diagnostics: DiagnosticLog,
+    errors: ErrorSample,
+    history: std::collections::VecDeque<Snapshot>,
+    space_time: std::collections::VecDeque<Snapshot>,
+    initial_mass: f64,
+    initial_range: (f64, f64),
+    u_initial: Vec<f64>,
+    buffer: Buffer,
+    previous_diff: Option<(nalgebra::DVector<f64>, f64)>,
+    proposed_dt: Option<f64>,
 }
 
-fn lax_wendroff_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    let n = u.len();
-    let dx = desc.delta_x;
-    let p = -desc.vel * desc.delta_t;
+impl Scenario {
+    pub fn new(desc: Descriptor) -> Result<Self, String> {
+        validate(&desc)?;
 
-    let mut ret = nalgebra::DVector::zeros(n);
+        let n = discretize(desc.bound, &desc);
+        let buffer = match desc.dimension {
+            Dimension::One => Buffer::new(init_wave(n, &desc), &desc),
+            Dimension::Two => Buffer::Plane {
+                u: init_plane(n, &desc),
+            },
+        };
 
-    for i in 1..u.len() - 1 {
-        let grad_1 = (u[i + 1] - u[i - 1]) / (2.0 * dx);
-        let grad_2 = (u[i + 1] - 2.0 * u[i] + u[i - 1]) / (2.0 * dx * dx);
-        ret[i] = grad_1 * p + grad_2 * p * p;
+        let mut scenario = Self {
+            initial_mass: initial_mass(&desc),
+            initial_range: initial_range(&desc),
+            u_initial: initial_values(&desc),
+            buffer,
+            previous_diff: None,
+            proposed_dt: None,
+            dt: desc.delta_t,
+            diverged: None,
+            shock: None,
+            color: egui::Color32::RED,
+            temporal_comparison: false,
+            reversed: false,
+            total_variation_history: TimeSeries::new(HISTORY_CAPACITY),
+            peak_history: TimeSeries::new(HISTORY_CAPACITY),
+            energy_history: TimeSeries::new(HISTORY_CAPACITY),
+            diagnostics: DiagnosticLog::default(),
+            errors: ErrorSample::default(),
+            history: std::collections::VecDeque::new(),
+            space_time: std::collections::VecDeque::new(),
+            desc,
+            t: 0.0,
+        };
+        scenario.record();
+        Ok(scenario)
     }
 
-    ret
+    pub fn apply(&mut self, desc: Descriptor) -> Result<(), String> {
+        validate(&desc)?;
+
+        if desc.dimension != self.desc.dimension {
+            return Err("Changing the dimension requires a new scenario".to_string());
+        }
+
+        let mut buffer = match &self.buffer {
+            Buffer::Base { u } | Buffer::CIP { u, .. } | Buffer::Unfolded { u, .. } => {
+                Buffer::new(resample(u, &self.desc, &desc), &desc)
+            }
+            Buffer::Plane { u } => Buffer::Plane {
+                u: resample_plane(u, &self.desc, &desc),
+            },
+        };
+        if let (Buffer::CIP { g, .. }, Buffer::CIP { g: regridded, .. }) =
+            (&self.buffer, &mut buffer)
+        {
+            *regridded = resample(g, &self.desc, &desc);
+        }
+        self.buffer = buffer;
+        self.initial_mass = initial_mass(&desc);
+        self.initial_range = initial_range(&desc);
+        self.u_initial = initial_values(&desc);
+        self.previous_diff = None;
+        self.proposed_dt = None;
+        self.desc = desc;
+        self.history.clear();
+        self.space_time.clear();
+        self.errors = self.measure_errors();
+        Ok(())
+    }
+
+    pub fn regrid(&mut self, delta_x: f64) -> Result<(), String> {
+        let mut desc = self.desc.clone();
+        desc.delta_x = delta_x;
+        desc.cells = discretize(desc.bound, &desc);
+        self.apply(desc)
+    }
+
+    pub fn reset(&mut self) {
+        let desc = &self.desc;
+        let fill = |u: &mut nalgebra::DVector<f64>| {
+            u.iter_mut()
+                .enumerate()
+                .for_each(|(i, u)| *u = initial_value(node_position(i as isize, desc), desc));
+        };
+
+        match &mut self.buffer {
+            Buffer::Base { u } => fill(u),
+            Buffer::CIP { u, g } => {
+                fill(u);
+                *g = gradient(u, desc);
+            }
+            Buffer::Unfolded { u, unfolded } => {
+                fill(u);
+                **unfolded = Buffer::new(unfold(u), &unfolded_desc(desc));
+            }
+            Buffer::Plane { u } => {
+                let (n_x, n_y) = u.shape();
+                for j in 0..n_y {
+                    for i in 0..n_x {
+                        u[(i, j)] = plane_value(i, j, desc);
+                    }
+                }
+            }
+        }
+
+        self.t = 0.0;
+        self.reversed = false;
+        self.previous_diff = None;
+        self.proposed_dt = None;
+        self.diverged = None;
+        self.shock = None;
+        self.total_variation_history.clear();
+        self.peak_history.clear();
+        self.energy_history.clear();
+        self.diagnostics.clear();
+        self.history.clear();
+        self.space_time.clear();
+        self.record();
+    }
+
+    pub fn forward(&mut self) -> f64 {
+        let dt = self.time_step();
+        self.step(dt, false)
+    }
+
+    pub fn forward_with(&mut self, dt: f64) -> f64 {
+        self.step(dt, true)
+    }
+
+    fn step(&mut self, dt: f64, exact: bool) -> f64 {
+        let mut desc = self.desc.clone();
+        desc.delta_t = dt;
+        if self.reversed {
+            desc.vel = -desc.vel;
+            desc.vel_y = -desc.vel_y;
+        }
+        let dt = match &mut self.buffer {
+            Buffer::Base { u } if desc.temporal_scheme == TemporalScheme::Rk45 => {
+                let mut taken = 0.0;
+                loop {
+                    let proposed = self.proposed_dt.unwrap_or(dt);
+                    desc.delta_t = proposed.min(dt - taken);
+                    let (v, h, next) = dormand_prince(u, &desc);
+                    *u = v;
+                    taken += h;
+
+                    let clipped = h == desc.delta_t && desc.delta_t < proposed;
+                    self.proposed_dt = Some(if clipped { proposed } else { next });
+                    // A diverged state keeps rejecting steps, so stop short
+                    // instead of shrinking towards zero.
+                    if !exact || !u.iter().all(|u| u.is_finite()) {
+                        break taken;
+                    }
+                    if dt - taken <= 1e-12 * dt {
+                        break dt;
+                    }
+                }
+            }
+            buffer => {
+                advance_buffer(buffer, &mut self.previous_diff, &desc);
+                dt
+            }
+        };
+        if let Some((min, max)) = desc.clamp_bounds {
+            self.buffer.clamp(min, max);
+        }
+
+        self.t += if self.reversed { -dt } else { dt };
+        self.dt = dt;
+        if self.diverged.is_none() && !self.values().iter().all(|u| u.is_finite()) {
+            self.diverged = Some(self.t);
+        }
+        let detect_shock = self.desc.break_on_shock && self.desc.equation == Equation::Burgers;
+        if detect_shock && self.shock.is_none() && SHOCK_JUMP_FRACTION < self.steepness() {
+            self.shock = Some(self.t);
+        }
+        self.record();
+        dt
+    }
+
+    pub fn shock_formed(&self) -> bool {
+        self.shock == Some(self.t)
+    }
+
+    fn steepness(&self) -> f64 {
+        let u = match &self.buffer {
+            Buffer::Base { u } | Buffer::CIP { u, .. } | Buffer::Unfolded { u, .. } => u,
+            Buffer::Plane { .. } => return 0.0,
+        };
+
+        let range = u.max() - u.min();
+        if range <= 0.0 {
+            return 0.0;
+        }
+
+        (0..u.len())
+            .map(|i| u[i] - boundary_value(u, i as isize + 1, &self.desc))
+            .fold(0.0, f64::max)
+            / range
+    }
+
+    fn record(&mut self) {
+        let total_variation = self.total_variation();
+        self.total_variation_history.push(self.t, total_variation);
+        let peak_ratio = self.peak_ratio();
+        self.peak_history.push(self.t, peak_ratio);
+        let energy = self.energy();
+        self.energy_history.push(self.t, energy);
+
+        if self.diagnostics.due(self.desc.diagnostic_stride) {
+            self.errors = self.measure_errors();
+            let sample = DiagnosticSample {
+                t: self.t,
+                mass: self.total_mass(),
+                total_variation,
+                energy,
+                l_2: self.errors.l_2,
+            };
+            self.diagnostics.push(sample);
+        }
+
+        if self.desc.record_history {
+            while self.desc.history_length <= self.history.len() {
+                self.history.pop_front();
+            }
+            self.history.push_back(Snapshot {
+                t: self.t,
+                values: self.values().to_vec(),
+            });
+        }
+
+        if !matches!(self.buffer, Buffer::Plane { .. }) {
+            if self.space_time.len() == SPACE_TIME_ROWS {
+                self.space_time.pop_front();
+            }
+            self.space_time.push_back(Snapshot {
+                t: self.t,
+                values: self.values().to_vec(),
+            });
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        if self.reversed {
+            return self.t <= 0.5 * self.time_step();
+        }
+
+        self.desc
+            .t_final
+            .is_some_and(|t_final| t_final - 0.5 * self.time_step() <= self.t)
+    }
+
+    pub fn reversed(&self) -> bool {
+        self.reversed
+    }
+
+    pub fn set_reversed(&mut self, reversed: bool) -> Result<(), String> {
+        if reversed && self.desc.equation != Equation::LinearAdvection {
+            return Err("Running backward requires linear advection".to_string());
+        }
+
+        if self.reversed != reversed {
+            self.reversed = reversed;
+            self.previous_diff = None;
+            self.proposed_dt = None;
+        }
+        Ok(())
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn advance_to(&mut self, t: f64) {
+        while self.t < t && self.diverged.is_none() {
+            let remaining = t - self.t;
+            if self.time_step() < remaining {
+                self.forward();
+            } else {
+                self.forward_with(remaining);
+                self.t = t;
+            }
+        }
+    }
+
+    fn time_step(&self) -> f64 {
+        let dt = self.proposed_dt.unwrap_or_else(|| self.trial_step());
+        match self.remaining() {
+            Some(remaining)
+                if self.desc.temporal_scheme == TemporalScheme::Rk45 && 0.0 < remaining =>
+            {
+                dt.min(remaining)
+            }
+            _ => dt,
+        }
+    }
+
+    fn remaining(&self) -> Option<f64> {
+        if self.reversed {
+            return Some(self.t);
+        }
+        self.desc.t_final.map(|t_final| t_final - self.t)
+    }
+
+    fn trial_step(&self) -> f64 {
+        let desc = &self.desc;
+        if !desc.adaptive {
+            return desc.delta_t;
+        }
+
+        let speed = match &self.buffer {
+            Buffer::Base { u } | Buffer::CIP { u, .. } | Buffer::Unfolded { u, .. } => (0..u.len())
+                .map(|i| wave_speed(u[i], cell_position(i, u.len(), desc), desc).abs())
+                .fold(0.0, f64::max),
+            Buffer::Plane { u } => {
+                let mut desc_y = desc.clone();
+                desc_y.vel = desc.vel_y;
+                desc_y.velocity_field = VelocityField::Constant;
+
+                let (n_x, n_y) = u.shape();
+                (0..n_y)
+                    .flat_map(|j| (0..n_x).map(move |i| (i, j)))
+                    .map(|(i, j)| {
+                        let x = cell_position(i, n_x, desc);
+                        let y = cell_position(j, n_y, desc);
+                        wave_speed(u[(i, j)], x, desc).abs()
+                            + wave_speed(u[(i, j)], y, &desc_y).abs()
+                    })
+                    .fold(0.0, f64::max)
+            }
+        };
+
+        if 0.0 < speed {
+            desc.target_cfl * desc.min_spacing() / speed
+        } else {
+            desc.delta_t
+        }
+    }
+
+    pub fn total_mass(&self) -> f64 {
+        self.weighted_sum(self.values().iter().copied())
+    }
+
+    pub fn total_variation(&self) -> f64 {
+        let variation = |u: nalgebra::DVectorView<f64>| {
+            u.iter()
+                .zip(u.iter().skip(1))
+                .map(|(a, b)| (b - a).abs())
+                .sum::<f64>()
+        };
+
+        match &self.buffer {
+            Buffer::Base { u } | Buffer::CIP { u, .. } | Buffer::Unfolded { u, .. } => {
+                variation(u.as_view())
+            }
+            Buffer::Plane { u } => {
+                let x = u.column_iter().map(|c| variation(c.as_view())).sum::<f64>();
+                let y = u
+                    .row_iter()
+                    .map(|r| variation(r.transpose().as_view()))
+                    .sum::<f64>();
+                x + y
+            }
+        }
+    }
+
+    pub fn energy(&self) -> f64 {
+        self.weighted_sum(self.values().iter().map(|u| u * u))
+            .sqrt()
+    }
+
+    pub fn mass_drift(&self) -> f64 {
+        self.total_mass() - self.initial_mass
+    }
+
+    pub fn peak_ratio(&self) -> f64 {
+        let peak = self
+            .values()
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        peak / self.initial_range.1
+    }
+
+    pub fn initial_range(&self) -> (f64, f64) {
+        self.initial_range
+    }
+
+    pub fn value_range(&self, frame: Option<usize>) -> (f64, f64) {
+        self.frame(frame)
+            .1
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), u| {
+                (min.min(*u), max.max(*u))
+            })
+    }
+
+    pub fn centroid(&self) -> f64 {
+        self.centroid_of(self.values())
+    }
+
+    pub fn phase_error(&self) -> f64 {
+        let exact = self.centroid_of(&self.exact_values(self.t));
+        self.centroid() - exact
+    }
+
+    fn centroid_of(&self, values: &[f64]) -> f64 {
+        if self.desc.stretching == 0.0 || matches!(self.buffer, Buffer::Plane { .. }) {
+            return centroid(values, self.row_len(), self.desc.delta_x);
+        }
+
+        let moment = values
+            .iter()
+            .enumerate()
+            .map(|(i, u)| node_position(i as isize, &self.desc) * u);
+        self.weighted_sum(moment) / self.weighted_sum(values.iter().copied())
+    }
+
+    /// The error norms as of the last diagnostic sample, which only refreshes
+    /// every `diagnostic_stride` steps because the exact solution can be
+    /// expensive to evaluate.
+    pub fn errors(&self) -> &ErrorSample {
+        &self.errors
+    }
+
+    fn measure_errors(&self) -> ErrorSample {
+        let (l_1, l_2, l_inf) = self.error_norms();
+        ErrorSample {
+            t: self.t,
+            l_1,
+            l_2,
+            l_inf,
+            l_2_quad: self.l2_error_quad(),
+            phase: self.phase_error(),
+        }
+    }
+
+    pub fn error_norms(&self) -> (f64, f64, f64) {
+        let errors = self
+            .values()
+            .iter()
+            .zip(self.exact_values(self.t))
+            .map(|(u, exact)| (u - exact).abs())
+            .collect::<Vec<_>>();
+
+        let l_1 = self.weighted_sum(errors.iter().copied());
+        let l_2 = self.weighted_sum(errors.iter().map(|e| e * e)).sqrt();
+        let l_inf = errors.iter().copied().fold(0.0, f64::max);
+        (l_1, l_2, l_inf)
+    }
+
+    fn weighted_sum<I: Iterator<Item = f64>>(&self, values: I) -> f64 {
+        if self.desc.stretching == 0.0 || matches!(self.buffer, Buffer::Plane { .. }) {
+            return values.sum::<f64>() * self.cell_volume();
+        }
+
+        let n = self.values().len();
+        values
+            .enumerate()
+            .map(|(i, value)| value * node_width(i, n, &self.desc))
+            .sum()
+    }
+
+    pub fn l2_error_quad(&self) -> f64 {
+        let u = match &self.buffer {
+            Buffer::Base { u } | Buffer::CIP { u, .. } | Buffer::Unfolded { u, .. } => u,
+            Buffer::Plane { .. } => return self.error_norms().1,
+        };
+        if self.desc.stretching != 0.0 {
+            return self.error_norms().1;
+        }
+
+        let dx = self.desc.delta_x;
+        let nodes = [-0.5 * (0.6_f64).sqrt(), 0.0, 0.5 * (0.6_f64).sqrt()];
+        let weights = [5.0 / 18.0, 8.0 / 18.0, 5.0 / 18.0];
+        let sum = (0..u.len())
+            .map(|i| {
+                nodes
+                    .iter()
+                    .zip(weights)
+                    .map(|(s, w)| {
+                        let u_h = (-2..=2)
+                            .map(|k| {
+                                let basis = (-2..=2)
+                                    .filter(|m| *m != k)
+                                    .map(|m| (s - m as f64) / (k - m) as f64)
+                                    .product::<f64>();
+                                basis * boundary_value(u, i as isize + k, &self.desc)
+                            })
+                            .sum::<f64>();
+                        let x = (i as f64 + s) * dx;
+                        w * (u_h - exact_value(x, self.t, &self.desc)).powi(2)
+                    })
+                    .sum::<f64>()
+            })
+            .sum::<f64>();
+
+        (sum * dx).sqrt()
+    }
+
+    pub fn file_stem(&self) -> String {
+        format!(
+            "{:?}_{:?}_t{:.3}",
+            self.desc.spatial_scheme, self.desc.temporal_scheme, self.t
+        )
+    }
+
+    pub fn export_diagnostics(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.diagnostics.to_json())
+    }
+
+    pub fn export_csv(&self, path: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let dx = self.desc.delta_x;
+
+        match &self.buffer {
+            Buffer::Base { u } | Buffer::Unfolded { u, .. } => {
+                writeln!(file, "x,u")?;
+                for (i, u) in u.iter().enumerate() {
+                    writeln!(file, "{},{}", node_position(i as isize, &self.desc), u)?;
+                }
+            }
+            Buffer::CIP { u, g } => {
+                writeln!(file, "x,u,g")?;
+                for (i, (u, g)) in u.iter().zip(g.iter()).enumerate() {
+                    let x = node_position(i as isize, &self.desc);
+                    writeln!(file, "{},{},{}", x, u, g)?;
+                }
+            }
+            Buffer::Plane { u } => {
+                writeln!(file, "x,y,u")?;
+                for (j, column) in u.column_iter().enumerate() {
+                    for (i, u) in column.iter().enumerate() {
+                        writeln!(file, "{},{},{}", i as f64 * dx, j as f64 * dx, u)?;
+                    }
+                }
+            }
+        }
+
+        file.flush()
+    }
+
+    fn row_len(&self) -> usize {
+        match &self.buffer {
+            Buffer::Plane { u } => u.nrows(),
+            _ => self.values().len(),
+        }
+    }
+
+    fn values(&self) -> &[f64] {
+        match &self.buffer {
+            Buffer::Base { u } => u.as_slice(),
+            Buffer::CIP { u, .. } => u.as_slice(),
+            Buffer::Plane { u } => u.as_slice(),
+            Buffer::Unfolded { u, .. } => u.as_slice(),
+        }
+    }
+
+    fn exact_values(&self, t: f64) -> Vec<f64> {
+        let dx = self.desc.delta_x;
+
+        match &self.buffer {
+            Buffer::Base { u } | Buffer::CIP { u, .. } | Buffer::Unfolded { u, .. } => (0..u.len())
+                .map(|i| exact_value(node_position(i as isize, &self.desc), t, &self.desc))
+                .collect(),
+            Buffer::Plane { u } => {
+                let (n_x, n_y) = u.shape();
+                (0..n_y)
+                    .flat_map(|j| {
+                        (0..n_x).map(move |i| {
+                            exact_plane_value(i as f64 * dx, j as f64 * dx, t, &self.desc)
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn cell_volume(&self) -> f64 {
+        match self.buffer {
+            Buffer::Plane { .. } => self.desc.delta_x * self.desc.delta_x,
+            _ => self.desc.delta_x,
+        }
+    }
+
+    pub fn show_space_time(&self, ui: &mut egui_plot::PlotUi) {
+        let (Some(first), Some(last)) = (self.space_time.front(), self.space_time.back()) else {
+            return;
+        };
+
+        let n_x = first.values.len();
+        let u = nalgebra::DMatrix::from_fn(n_x, self.space_time.len(), |i, j| {
+            self.space_time[j].values[i]
+        });
+
+        let dx = self.desc.delta_x;
+        let width = n_x as f64 * dx;
+        let height = last.t - first.t + self.dt;
+        let center = egui_plot::PlotPoint::new((width - dx) / 2.0, (first.t + last.t) / 2.0);
+        show_heatmap(&u, "space_time", center, [width as f32, height as f32], ui);
+    }
+
+    pub fn frame_time(&self, frame: Option<usize>) -> f64 {
+        self.frame(frame).0
+    }
+
+    fn frame(&self, frame: Option<usize>) -> (f64, &[f64]) {
+        match frame.and_then(|i| self.history.get(i)) {
+            Some(snapshot) => (snapshot.t, snapshot.values.as_slice()),
+            None => (self.t, self.values()),
+        }
+    }
+
+    pub fn data_bounds(&self, frame: Option<usize>) -> egui_plot::PlotBounds {
+        let dx = self.desc.delta_x;
+        let (t, values) = self.frame(frame);
+
+        if let Buffer::Plane { u } = &self.buffer {
+            let (n_x, n_y) = u.shape();
+            return egui_plot::PlotBounds::from_min_max(
+                [-0.5 * dx, -0.5 * dx],
+                [(n_x as f64 - 0.5) * dx, (n_y as f64 - 0.5) * dx],
+            );
+        }
+
+        let mut bounds = egui_plot::PlotBounds::NOTHING;
+        bounds.extend_with_x(0.0);
+        bounds.extend_with_x(node_position(values.len() as isize - 1, &self.desc));
+        values
+            .iter()
+            .chain(self.exact_values(t).iter())
+            .filter(|y| y.is_finite())
+            .for_each(|y| bounds.extend_with_y(*y));
+        bounds
+    }
+
+    pub fn show_exact(&self, ui: &mut egui_plot::PlotUi, frame: Option<usize>) {
+        if let Buffer::Plane { .. } = &self.buffer {
+            return;
+        }
+
+        let (t, _) = self.frame(frame);
+        let exact = self
+            .exact_values(t)
+            .into_iter()
+            .enumerate()
+            .map(|(i, y)| [node_position(i as isize, &self.desc), y])
+            .collect::<Vec<_>>();
+
+        let exact = egui_plot::Line::new(exact)
+            .color(egui::Color32::GREEN)
+            .name("Exact");
+
+        ui.add(exact);
+    }
+
+    pub fn show_inside(
+        &mut self,
+        ui: &mut egui_plot::PlotUi,
+        frame: Option<usize>,
+        color: egui::Color32,
+    ) {
+        let (_, values) = self.frame(frame);
+
+        if let Buffer::Plane { u } = &self.buffer {
+            let (n_x, n_y) = u.shape();
+            let u = nalgebra::DMatrix::from_column_slice(n_x, n_y, values);
+            show_plane(&u, &self.desc, ui);
+            return;
+        }
+
+        let u = nalgebra::DVector::from_column_slice(values);
+
+        if self.desc.show_initial {
+            let points = self
+                .u_initial
+                .iter()
+                .enumerate()
+                .map(|(i, u)| [node_position(i as isize, &self.desc), *u])
+                .collect::<Vec<_>>();
+            let line = egui_plot::Line::new(points)
+                .color(egui::Color32::GRAY.gamma_multiply(0.4))
+                .name("Initial");
+            ui.add(line);
+        }
+
+        let points = u
+            .iter()
+            .enumerate()
+            .map(|(i, y)| [node_position(i as isize, &self.desc), *y])
+            .collect::<Vec<_>>();
+
+        let name = format!(
+            "{:?} / {:?}",
+            self.desc.spatial_scheme, self.desc.temporal_scheme
+        );
+        match self.desc.plot_style {
+            PlotStyle::Points => {
+                let points = egui_plot::Points::new(points)
+                    .radius(4.0)
+                    .color(color)
+                    .name(name);
+                ui.add(points);
+            }
+            PlotStyle::Line => {
+                let line = egui_plot::Line::new(points).color(color).name(name);
+                ui.add(line);
+            }
+        }
+
+        if self.desc.show_gradient {
+            let current = frame.and_then(|i| self.history.get(i)).is_none();
+            let g = match &self.buffer {
+                Buffer::CIP { g, .. } if current => g.clone(),
+                _ => gradient(&u, &self.desc),
+            };
+
+            let points = g
+                .iter()
+                .enumerate()
+                .map(|(i, g)| [node_position(i as isize, &self.desc), *g])
+                .collect::<Vec<_>>();
+            let line = egui_plot::Line::new(points)
+                .color(color)
+                .style(egui_plot::LineStyle::dashed_dense())
+                .name(format!("du/dx {:?}", self.desc.spatial_scheme));
+            ui.add(line);
+        }
+
+        let weno_scheme = matches!(
+            self.desc.spatial_scheme,
+            SpatialScheme::WENO | SpatialScheme::WenoZ
+        );
+        if self.desc.show_weights && weno_scheme {
+            let weights = weno_weights(&u, &self.desc);
+            let styles = [
+                egui_plot::LineStyle::Solid,
+                egui_plot::LineStyle::dashed_dense(),
+                egui_plot::LineStyle::dotted_dense(),
+            ];
+            for (k, style) in styles.into_iter().enumerate() {
+                let points = weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| [node_position(i as isize, &self.desc), w[k]])
+                    .collect::<Vec<_>>();
+                let line = egui_plot::Line::new(points)
+                    .color(color.gamma_multiply(0.4))
+                    .style(style)
+                    .name(format!("w{} {:?}", k + 1, self.desc.spatial_scheme));
+                ui.add(line);
+            }
+        }
+
+        let limited_scheme = matches!(
+            self.desc.spatial_scheme,
+            SpatialScheme::MUSCL | SpatialScheme::FluxLimited
+        );
+        if self.desc.show_limiter && limited_scheme {
+            let limited = limiter_activity(&u, &self.desc)
+                .into_iter()
+                .zip(u.iter())
+                .enumerate()
+                .filter(|(_, (limited, _))| *limited)
+                .map(|(i, (_, y))| [node_position(i as isize, &self.desc), *y])
+                .collect::<Vec<_>>();
+
+            let limited = egui_plot::Points::new(limited)
+                .radius(5.0)
+                .color(egui::Color32::YELLOW)
+                .name("Limited");
+            ui.add(limited);
+        }
+    }
+}
+
+fn centroid(values: &[f64], row_len: usize, delta_x: f64) -> f64 {
+    let (moment, mass) = values
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(moment, mass), (k, u)| {
+            let x = (k % row_len) as f64 * delta_x;
+            (moment + x * u, mass + u)
+        });
+    moment / mass
+}
+
+#[derive(Clone, Debug)]
+pub struct ConvergenceRow {
+    pub delta_x: f64,
+    pub l_2: f64,
+    pub order: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+pub struct Progress {
+    fraction: std::sync::atomic::AtomicU64,
+    level: std::sync::atomic::AtomicUsize,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f64 {
+        f64::from_bits(self.fraction.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    pub fn level(&self) -> usize {
+        self.level.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    fn set(&self, level: usize, fraction: f64) {
+        self.level
+            .store(level, std::sync::atomic::Ordering::Relaxed);
+        self.fraction
+            .store(fraction.to_bits(), std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+pub fn forward_all(scenarios: &mut [Scenario]) -> Option<f64> {
+    let diverged = scenarios.iter().any(|scenario| scenario.diverged.is_some());
+    let finished = scenarios
+        .first()
+        .is_some_and(|scenario| scenario.finished());
+
+    match scenarios.split_first_mut() {
+        Some((primary, others)) if !diverged && !finished => {
+            let dt = primary.forward();
+            others.iter_mut().for_each(|scenario| {
+                scenario.forward_with(dt);
+            });
+            Some(dt)
+        }
+        _ => None,
+    }
+}
+
+pub fn convergence_study(
+    desc: &Descriptor,
+    t_final: f64,
+    levels: usize,
+    progress: &Progress,
+) -> Result<Vec<ConvergenceRow>, String> {
+    let mut rows: Vec<ConvergenceRow> = vec![];
+    let mut desc = desc.clone();
+    desc.t_final = Some(t_final);
+
+    for level in 0..levels {
+        let mut scenario = Scenario::new(desc.clone())?;
+        while !scenario.finished() {
+            scenario.forward();
+            let fraction = (level as f64 + (scenario.t / t_final).min(1.0)) / levels as f64;
+            progress.set(level, fraction);
+        }
+
+        let l_2 = scenario.l2_error_quad();
+        let order = rows.last().map(|row| (row.l_2 / l_2).log2());
+        rows.push(ConvergenceRow {
+            delta_x: desc.delta_x,
+            l_2,
+            order,
+        });
+
+        desc.delta_x /= 2.0;
+        desc.delta_t /= 2.0;
+    }
+
+    progress.set(levels, 1.0);
+    Ok(rows)
+}
+
+pub const LINEAR_SCHEMES: [SpatialScheme; 6] = [
+    SpatialScheme::Central,
+    SpatialScheme::Upwind,
+    SpatialScheme::LaxWendroff,
+    SpatialScheme::BeamWarming,
+    SpatialScheme::Fromm,
+    SpatialScheme::QUICK,
+];
+
+#[derive(Clone, Debug)]
+pub struct ModifiedWavenumber {
+    pub k_dx: f64,
+    pub real: f64,
+    pub imag: f64,
+}
+
+pub fn modified_wavenumber(
+    spatial_scheme: SpatialScheme,
+    cfl: f64,
+    samples: usize,
+) -> Vec<ModifiedWavenumber> {
+    let n = 2 * samples;
+    let mut desc = Descriptor::new();
+    desc.spatial_scheme = spatial_scheme;
+    desc.delta_x = 1.0;
+    desc.bound = n as f64;
+    desc.delta_t = cfl.max(1e-6);
+
+    let diff_fn = diff_operator(spatial_scheme);
+    let radius = spatial_scheme.stencil_radius();
+    (1..=samples)
+        .map(|m| {
+            let k_dx = std::f64::consts::TAU * m as f64 / n as f64;
+            let response = |phase: f64| {
+                let u = nalgebra::DVector::from_fn(n, |j, _| (k_dx * j as f64 + phase).cos());
+                with_boundary(&u, diff_fn, radius, &desc)[0] / -desc.delta_t
+            };
+
+            ModifiedWavenumber {
+                k_dx,
+                real: response(-std::f64::consts::FRAC_PI_2),
+                imag: -response(0.0),
+            }
+        })
+        .collect()
+}
+
+fn show_plane(u: &nalgebra::DMatrix<f64>, desc: &Descriptor, ui: &mut egui_plot::PlotUi) {
+    let (n_x, n_y) = u.shape();
+    let dx = desc.delta_x;
+    let width = n_x as f64 * dx;
+    let height = n_y as f64 * dx;
+    let center = egui_plot::PlotPoint::new((width - dx) / 2.0, (height - dx) / 2.0);
+    show_heatmap(u, "plane", center, [width as f32, height as f32], ui);
+}
+
+fn show_heatmap(
+    u: &nalgebra::DMatrix<f64>,
+    name: &str,
+    center: egui_plot::PlotPoint,
+    size: [f32; 2],
+    ui: &mut egui_plot::PlotUi,
+) {
+    let (n_x, n_y) = u.shape();
+    let (lower, upper) = (u.min(), u.max());
+    let range = if lower < upper { upper - lower } else { 1.0 };
+
+    let mut image = egui::ColorImage::new([n_x, n_y], egui::Color32::BLACK);
+    for j in 0..n_y {
+        for i in 0..n_x {
+            image[(i, n_y - 1 - j)] = color_map((u[(i, j)] - lower) / range);
+        }
+    }
+
+    let texture = ui
+        .ctx()
+        .load_texture(name, image, egui::TextureOptions::NEAREST);
+    ui.image(egui_plot::PlotImage::new(&texture, center, size));
+}
+
+fn color_map(v: f64) -> egui::Color32 {
+    const STOPS: [[f64; 3]; 4] = [
+        [0.0, 0.0, 255.0],
+        [0.0, 255.0, 255.0],
+        [255.0, 255.0, 0.0],
+        [255.0, 0.0, 0.0],
+    ];
+
+    let s = v.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let k = (s.floor() as usize).min(STOPS.len() - 2);
+    let w = s - k as f64;
+    let c = |l: usize| ((1.0 - w) * STOPS[k][l] + w * STOPS[k + 1][l]) as u8;
+    egui::Color32::from_rgb(c(0), c(1), c(2))
+}
+
+fn advance_buffer(
+    buffer: &mut Buffer,
+    previous_diff: &mut Option<(nalgebra::DVector<f64>, f64)>,
+    desc: &Descriptor,
+) {
+    match buffer {
+        Buffer::Base { u } => {
+            let low = advance(u, previous_diff, desc);
+            *u = if desc.fct && desc.spatial_scheme == SpatialScheme::Upwind {
+                flux_corrected(u, &low, desc)
+            } else {
+                low
+            };
+        }
+        Buffer::CIP { u, g } => {
+            let n = u.len();
+            let r = SpatialScheme::CIP.stencil_radius();
+            let (u_1, g_1) = cip(&extend(u, r, desc), &extend_gradient(g, r, desc), desc);
+            *u = u_1.rows(r, n).into_owned();
+            *g = g_1.rows(r, n).into_owned();
+        }
+        Buffer::Plane { u } => {
+            let mut desc = desc.clone();
+            for mut column in u.column_iter_mut() {
+                let v = advance(&column.clone_owned(), &mut None, &desc);
+                column.copy_from(&v);
+            }
+
+            desc.vel = desc.vel_y;
+            desc.velocity_field = VelocityField::Constant;
+            for mut row in u.row_iter_mut() {
+                let v = advance(&row.transpose(), &mut None, &desc);
+                row.copy_from(&v.transpose());
+            }
+        }
+        Buffer::Unfolded { u, unfolded } => {
+            advance_buffer(unfolded, previous_diff, &unfolded_desc(desc));
+            let n = u.len();
+            let v = match unfolded.as_ref() {
+                Buffer::Base { u } | Buffer::CIP { u, .. } => u,
+                _ => unreachable!(),
+            };
+            *u = nalgebra::DVector::from_fn(n, |i, _| v[i] + v[2 * n - 1 - i]);
+        }
+    }
+}
+
+fn unfolded_desc(desc: &Descriptor) -> Descriptor {
+    let mut desc = desc.clone();
+    desc.bound = 2.0 * domain_length(&desc);
+    desc.boundary_condition = BoundaryCondition::Periodic;
+    desc
+}
+
+fn unfold(u: &nalgebra::DVector<f64>) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    nalgebra::DVector::from_fn(2 * n, |i, _| if i < n { u[i] } else { 0.0 })
+}
+
+fn advance(
+    u: &nalgebra::DVector<f64>,
+    previous_diff: &mut Option<(nalgebra::DVector<f64>, f64)>,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    if desc.spatial_scheme == SpatialScheme::SemiLagrangian {
+        return semi_lagrangian(u, desc);
+    }
+
+    let diff_fn = diff_operator(desc.spatial_scheme);
+    let radius = desc.spatial_scheme.stencil_radius();
+    let diff_fn =
+        |u: &nalgebra::DVector<f64>, desc: &Descriptor| with_boundary(u, diff_fn, radius, desc);
+
+    if desc.temporal_scheme == TemporalScheme::BackwardEuler {
+        backward_euler(u, desc)
+    } else if desc.temporal_scheme == TemporalScheme::CrankNicolson {
+        crank_nicolson(u, desc)
+    } else if desc.temporal_scheme == TemporalScheme::AdamsBashforth2 {
+        adams_bashforth2(u, diff_fn, previous_diff, desc)
+    } else {
+        let forward_fn = match desc.temporal_scheme {
+            TemporalScheme::ForwardEuler => forward_euler,
+            TemporalScheme::Rk2 => rk2,
+            TemporalScheme::Rk3 => rk3,
+            TemporalScheme::Rk4 => rk4,
+            TemporalScheme::TvdRk2 => tvd_rk2,
+            TemporalScheme::TvdRk3 => tvd_rk3,
+            TemporalScheme::TvdRk4 => tvd_rk4,
+            _ => unreachable!(),
+        };
+
+        forward_fn(u, diff_fn, desc)
+    }
+}
+
+fn diff_operator(spatial_scheme: SpatialScheme) -> DiffFn {
+    match spatial_scheme {
+        SpatialScheme::Central => central_diff,
+        SpatialScheme::Upwind => upwind_diff,
+        SpatialScheme::LaxWendroff => lax_wendroff_diff,
+        SpatialScheme::BeamWarming => beam_warming_diff,
+        SpatialScheme::Fromm => fromm_diff,
+        SpatialScheme::ENO => eno_diff,
+        SpatialScheme::WENO => weno_diff,
+        SpatialScheme::WenoZ => weno_z_diff,
+        SpatialScheme::MUSCL => muscl_diff,
+        SpatialScheme::FluxLimited => flux_limited_diff,
+        SpatialScheme::Godunov => godunov_diff,
+        SpatialScheme::Rusanov => rusanov_diff,
+        SpatialScheme::QUICK => quick_diff,
+        _ => unreachable!(),
+    }
+}
+
+const MIN_DELTA_X: f64 = 0.001;
+const MIN_DELTA_T: f64 = 0.0001;
+const MIN_CELLS: usize = 7;
+
+fn validate(desc: &Descriptor) -> Result<(), String> {
+    if desc.delta_x.is_nan() || desc.delta_x < MIN_DELTA_X {
+        return Err(format!("Delta Space must be at least {}", MIN_DELTA_X));
+    }
+
+    if !desc.delta_t.is_finite() || desc.delta_t <= 0.0 {
+        return Err("Delta Time must be positive".to_string());
+    }
+
+    if !desc.playback_speed.is_finite() || desc.playback_speed < 0.0 {
+        return Err("Playback Speed must be a non-negative number".to_string());
+    }
+
+    let n = discretize(desc.bound, desc);
+    let min_cells = MIN_CELLS.max(2 * desc.spatial_scheme.stencil_radius() + 1);
+    if n < min_cells {
+        return Err(format!(
+            "The grid has {} cells but at least {} are required",
+            n, min_cells
+        ));
+    }
+
+    if desc.adaptive && (desc.target_cfl.is_nan() || desc.target_cfl <= 0.0) {
+        return Err("Target CFL must be positive".to_string());
+    }
+
+    if desc.record_history && desc.history_length == 0 {
+        return Err("History Length must be at least 1".to_string());
+    }
+
+    if desc.diagnostic_stride == 0 {
+        return Err("Diagnostic Stride must be at least 1".to_string());
+    }
+
+    if desc
+        .t_final
+        .is_some_and(|t_final| t_final.is_nan() || t_final < 0.0)
+    {
+        return Err("Final Time must not be negative".to_string());
+    }
+
+    if desc
+        .clamp_bounds
+        .is_some_and(|(min, max)| min.is_nan() || max.is_nan() || min > max)
+    {
+        return Err("Clamp Min must not exceed Clamp Max".to_string());
+    }
+
+    if !desc.autoscale_y && (desc.y_min.is_nan() || desc.y_max.is_nan() || desc.y_min >= desc.y_max)
+    {
+        return Err("y min must be below y max".to_string());
+    }
+
+    if desc.dimension == Dimension::Two && desc.temporal_scheme == TemporalScheme::AdamsBashforth2 {
+        return Err("AdamsBashforth2 is only available in one dimension".to_string());
+    }
+
+    if desc.temporal_scheme == TemporalScheme::Rk45 {
+        if !desc.tolerance.is_finite() || desc.tolerance <= 0.0 {
+            return Err("Tolerance must be positive".to_string());
+        }
+        if desc.dimension == Dimension::Two {
+            return Err("Rk45 is only available in one dimension".to_string());
+        }
+        if desc.boundary_condition == BoundaryCondition::Reflective {
+            return Err("Rk45 does not support reflective walls".to_string());
+        }
+        let step_dependent = matches!(
+            desc.spatial_scheme,
+            SpatialScheme::LaxWendroff
+                | SpatialScheme::BeamWarming
+                | SpatialScheme::Fromm
+                | SpatialScheme::FluxLimited
+                | SpatialScheme::CIP
+                | SpatialScheme::RationalCIP
+                | SpatialScheme::SemiLagrangian
+        );
+        if step_dependent {
+            return Err(format!(
+                "Rk45 requires a method-of-lines scheme, not {:?}",
+                desc.spatial_scheme
+            ));
+        }
+    }
+
+    if desc.dimension == Dimension::One
+        && desc.boundary_condition == BoundaryCondition::Reflective
+        && desc.velocity_field != VelocityField::Constant
+    {
+        return Err("Reflective walls require a constant velocity field".to_string());
+    }
+
+    if desc.dimension == Dimension::Two && desc.fct && desc.spatial_scheme == SpatialScheme::Upwind
+    {
+        return Err("Flux-Corrected Transport is only available in one dimension".to_string());
+    }
+
+    if desc.fct
+        && desc.spatial_scheme == SpatialScheme::Upwind
+        && desc.temporal_scheme != TemporalScheme::ForwardEuler
+    {
+        return Err("Flux-Corrected Transport requires ForwardEuler".to_string());
+    }
+
+    if desc.dimension == Dimension::Two
+        && matches!(
+            desc.spatial_scheme,
+            SpatialScheme::CIP | SpatialScheme::RationalCIP
+        )
+    {
+        return Err("CIP is only available in one dimension".to_string());
+    }
+
+    if uses_interval(desc.initial_condition)
+        && (desc.x_1.is_nan() || desc.x_2.is_nan() || desc.x_2 <= desc.x_1)
+    {
+        return Err("x1 must be below x2".to_string());
+    }
+
+    if desc.initial_condition == InitialCondition::Expression {
+        if let Some(e) = desc.expression.error() {
+            return Err(format!("Invalid expression: {}", e));
+        }
+        let x = (0..n as isize)
+            .map(|i| node_position(i, desc))
+            .find(|x| !desc.expression.eval(*x).is_finite());
+        if let Some(x) = x {
+            return Err(format!("The expression is not finite at x = {:.3}", x));
+        }
+    }
+
+    if !matches!(desc.central_order, 2 | 4 | 6) {
+        return Err("Central order must be 2, 4 or 6".to_string());
+    }
+
+    if advection_form(desc)
+        && !matches!(
+            desc.spatial_scheme,
+            SpatialScheme::Central | SpatialScheme::Upwind
+        )
+    {
+        return Err("The advection form requires the Central or Upwind scheme".to_string());
+    }
+
+    if advection_form(desc) && desc.fct && desc.spatial_scheme == SpatialScheme::Upwind {
+        return Err("Flux-Corrected Transport requires the conservation form".to_string());
+    }
+
+    if !desc.stretching.is_finite() || desc.stretching < 0.0 {
+        return Err("Grid Stretching must be a non-negative number".to_string());
+    }
+
+    if desc.stretching != 0.0 {
+        if desc.dimension == Dimension::Two {
+            return Err("Grid stretching is only available in one dimension".to_string());
+        }
+        let second_order_central =
+            desc.spatial_scheme == SpatialScheme::Central && desc.central_order == 2;
+        if !second_order_central && desc.spatial_scheme != SpatialScheme::Upwind {
+            return Err(
+                "Grid stretching requires the Upwind or second-order Central scheme".to_string(),
+            );
+        }
+        if desc.boundary_condition == BoundaryCondition::Reflective {
+            return Err("Grid stretching does not support reflective walls".to_string());
+        }
+        if desc.fct {
+            return Err("Grid stretching does not support Flux-Corrected Transport".to_string());
+        }
+        if matches!(
+            desc.temporal_scheme,
+            TemporalScheme::BackwardEuler | TemporalScheme::CrankNicolson
+        ) {
+            return Err(format!(
+                "{:?} does not support grid stretching",
+                desc.temporal_scheme
+            ));
+        }
+    }
+
+    let implicit = matches!(
+        desc.temporal_scheme,
+        TemporalScheme::BackwardEuler | TemporalScheme::CrankNicolson
+    );
+    let linear = matches!(
+        desc.spatial_scheme,
+        SpatialScheme::Central | SpatialScheme::Upwind
+    );
+    if implicit && !linear {
+        return Err(format!(
+            "{:?} requires the Central or Upwind scheme",
+            desc.temporal_scheme
+        ));
+    }
+
+    if implicit && desc.spatial_scheme == SpatialScheme::Central && desc.central_order != 2 {
+        return Err(format!(
+            "{:?} requires the second-order Central stencil",
+            desc.temporal_scheme
+        ));
+    }
+
+    Ok(())
+}
+
+type DiffFn = fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>;
+
+fn discretize(x: f64, desc: &Descriptor) -> usize {
+    (x / desc.delta_x).round() as usize
+}
+
+fn domain_length(desc: &Descriptor) -> f64 {
+    discretize(desc.bound, desc) as f64 * desc.delta_x
+}
+
+fn init_wave(n: usize, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    nalgebra::DVector::from_fn(n, |i, _| {
+        initial_value(node_position(i as isize, desc), desc)
+    })
+}
+
+fn init_plane(n: usize, desc: &Descriptor) -> nalgebra::DMatrix<f64> {
+    nalgebra::DMatrix::from_fn(n, n, |i, j| plane_value(i, j, desc))
+}
+
+fn plane_value(i: usize, j: usize, desc: &Descriptor) -> f64 {
+    let dx = desc.delta_x;
+    initial_value(i as f64 * dx, desc) * initial_value(j as f64 * dx, desc)
+}
+
+fn initial_mass(desc: &Descriptor) -> f64 {
+    let n = discretize(desc.bound, desc);
+    match desc.dimension {
+        Dimension::One if desc.stretching != 0.0 => init_wave(n, desc)
+            .iter()
+            .enumerate()
+            .map(|(i, u)| u * node_width(i, n, desc))
+            .sum(),
+        Dimension::One => init_wave(n, desc).sum() * desc.delta_x,
+        Dimension::Two => init_plane(n, desc).sum() * desc.delta_x * desc.delta_x,
+    }
+}
+
+fn initial_values(desc: &Descriptor) -> Vec<f64> {
+    let n = discretize(desc.bound, desc);
+    match desc.dimension {
+        Dimension::One => init_wave(n, desc).as_slice().to_vec(),
+        Dimension::Two => init_plane(n, desc).as_slice().to_vec(),
+    }
+}
+
+fn initial_range(desc: &Descriptor) -> (f64, f64) {
+    let n = discretize(desc.bound, desc);
+    match desc.dimension {
+        Dimension::One => {
+            let u = init_wave(n, desc);
+            (u.min(), u.max())
+        }
+        Dimension::Two => {
+            let u = init_plane(n, desc);
+            (u.min(), u.max())
+        }
+    }
+}
+
+fn resample_plane(
+    u: &nalgebra::DMatrix<f64>,
+    from: &Descriptor,
+    to: &Descriptor,
+) -> nalgebra::DMatrix<f64> {
+    let columns = u
+        .column_iter()
+        .map(|column| resample(&column.clone_owned(), from, to))
+        .collect::<Vec<_>>();
+    let u = nalgebra::DMatrix::from_columns(&columns);
+
+    let rows = u
+        .row_iter()
+        .map(|row| resample(&row.transpose(), from, to).transpose())
+        .collect::<Vec<_>>();
+    nalgebra::DMatrix::from_rows(&rows)
+}
+
+fn resample(
+    u: &nalgebra::DVector<f64>,
+    from: &Descriptor,
+    to: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let n = discretize(to.bound, to);
+    if from.stretching != 0.0 || to.stretching != 0.0 {
+        let nodes = (0..=u.len())
+            .map(|j| node_position(j as isize, from))
+            .collect::<Vec<_>>();
+        return nalgebra::DVector::from_fn(n, |i, _| {
+            let x = node_position(i as isize, to);
+            let j = nodes
+                .partition_point(|x_j| *x_j <= x)
+                .saturating_sub(1)
+                .min(u.len() - 1);
+            let w = (x - nodes[j]) / (nodes[j + 1] - nodes[j]);
+            (1.0 - w) * boundary_value(u, j as isize, from)
+                + w * boundary_value(u, j as isize + 1, from)
+        });
+    }
+
+    nalgebra::DVector::from_fn(n, |i, _| {
+        let s = i as f64 * to.delta_x / from.delta_x;
+        let j = s.floor();
+        let w = s - j;
+        let j = j as isize;
+        (1.0 - w) * boundary_value(u, j, from) + w * boundary_value(u, j + 1, from)
+    })
+}
+
+fn initial_value(x: f64, desc: &Descriptor) -> f64 {
+    match desc.initial_condition {
+        InitialCondition::Square => square_wave(x, desc),
+        InitialCondition::Gaussian => gaussian(x, desc),
+        InitialCondition::Sine => sine(x, desc),
+        InitialCondition::Triangle => triangle(x, desc),
+        InitialCondition::Step => step(x, desc),
+        InitialCondition::Noise => noise(x, desc),
+        InitialCondition::TwoGaussians => two_gaussians(x, desc),
+        InitialCondition::Expression => desc.expression.eval(x),
+    }
+}
+
+fn exact_value(x: f64, t: f64, desc: &Descriptor) -> f64 {
+    match (desc.equation, desc.initial_condition) {
+        (Equation::Burgers, InitialCondition::Square) if 0.0 <= desc.amplitude => {
+            exact_burgers(x, t, desc)
+        }
+        (Equation::LinearAdvection, _) if desc.velocity_field != VelocityField::Constant => {
+            characteristic_value(x, t, desc)
+        }
+        (Equation::LinearAdvection, _)
+            if desc.boundary_condition == BoundaryCondition::Reflective =>
+        {
+            reflected_value(x, desc.vel * t, desc)
+        }
+        _ => shifted_value(x, desc.vel * t, desc),
+    }
+}
+
+const CHARACTERISTIC_STEP: f64 = 0.05;
+
+fn characteristic_value(x: f64, t: f64, desc: &Descriptor) -> f64 {
+    let length = domain_length(desc);
+    let rate = (desc.vel * desc.vel_freq).abs();
+    let steps = ((t * rate / CHARACTERISTIC_STEP).ceil() as usize).max(1);
+    let h = t / steps as f64;
+
+    let rhs = |x: f64| {
+        let x = x.rem_euclid(length);
+        (-velocity(x, desc), velocity_gradient(x, desc))
+    };
+
+    let (mut x, mut stretch) = (x, 0.0);
+    for _ in 0..steps {
+        let k_1 = rhs(x);
+        let k_2 = rhs(x + 0.5 * h * k_1.0);
+        let k_3 = rhs(x + 0.5 * h * k_2.0);
+        let k_4 = rhs(x + h * k_3.0);
+        x += h * (k_1.0 + 2.0 * k_2.0 + 2.0 * k_3.0 + k_4.0) / 6.0;
+        stretch += h * (k_1.1 + 2.0 * k_2.1 + 2.0 * k_3.1 + k_4.1) / 6.0;
+    }
+
+    let advective = advection_form(desc)
+        || matches!(
+            desc.spatial_scheme,
+            SpatialScheme::CIP | SpatialScheme::RationalCIP | SpatialScheme::SemiLagrangian
+        );
+    let factor = if advective { 1.0 } else { (-stretch).exp() };
+    initial_value(x.rem_euclid(length), desc) * factor
+}
+
+fn reflected_value(x: f64, shift: f64, desc: &Descriptor) -> f64 {
+    let length = domain_length(desc);
+    let unfolded = |x: f64| {
+        let x = (x - shift).rem_euclid(2.0 * length);
+        if x < length {
+            initial_value(x, desc)
+        } else {
+            0.0
+        }
+    };
+
+    unfolded(x) + unfolded(2.0 * length - desc.delta_x - x)
+}
+
+fn exact_burgers(x: f64, t: f64, desc: &Descriptor) -> f64 {
+    if t <= 0.0 {
+        return square_wave(x, desc);
+    }
+
+    let h = square_wave((desc.x_1 + desc.x_2) / 2.0, desc);
+    let width = desc.x_2 - desc.x_1;
+    let x = if x < desc.x_1 {
+        x + domain_length(desc)
+    } else {
+        x
+    };
+
+    let (head, shock) = if t < 2.0 * width / h {
+        (desc.x_1 + h * t, desc.x_2 + 0.5 * h * t)
+    } else {
+        let shock = desc.x_1 + (2.0 * h * width * t).sqrt();
+        (shock, shock)
+    };
+
+    if x < head {
+        (x - desc.x_1) / t
+    } else if x < shock {
+        h
+    } else {
+        0.0
+    }
+}
+
+fn exact_plane_value(x: f64, y: f64, t: f64, desc: &Descriptor) -> f64 {
+    shifted_value(x, desc.vel * t, desc) * shifted_value(y, desc.vel_y * t, desc)
+}
+
+fn shifted_value(x: f64, shift: f64, desc: &Descriptor) -> f64 {
+    initial_value((x - shift).rem_euclid(domain_length(desc)), desc)
+}
+
+fn uses_interval(initial_condition: InitialCondition) -> bool {
+    matches!(
+        initial_condition,
+        InitialCondition::Square | InitialCondition::Triangle
+    )
+}
+
+fn square_wave(x: f64, desc: &Descriptor) -> f64 {
+    if desc.x_1 <= x && x < desc.x_2 {
+        desc.amplitude
+    } else {
+        0.0
+    }
+}
+
+const MIN_SIGMA: f64 = 1e-6;
+
+fn gaussian(x: f64, desc: &Descriptor) -> f64 {
+    let center = (desc.x_1 + desc.x_2) / 2.0;
+    gaussian_bump(x, center, desc.amplitude, desc)
+}
+
+fn two_gaussians(x: f64, desc: &Descriptor) -> f64 {
+    gaussian_bump(x, desc.center_1, desc.amplitude_1, desc)
+        + gaussian_bump(x, desc.center_2, desc.amplitude_2, desc)
+}
+
+fn gaussian_bump(x: f64, center: f64, amplitude: f64, desc: &Descriptor) -> f64 {
+    let sigma = desc.sigma.max(MIN_SIGMA);
+    amplitude * (-(x - center).powi(2) / (2.0 * sigma * sigma)).exp()
+}
+
+fn sine(x: f64, desc: &Descriptor) -> f64 {
+    (2.0 * std::f64::consts::PI * x / domain_length(desc)).sin()
+}
+
+fn triangle(x: f64, desc: &Descriptor) -> f64 {
+    let center = (desc.x_1 + desc.x_2) / 2.0;
+    let half_width = (desc.x_2 - desc.x_1) / 2.0;
+    (1.0 - (x - center).abs() / half_width).max(0.0)
+}
+
+fn step(x: f64, desc: &Descriptor) -> f64 {
+    if x < desc.x_1 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn noise(x: f64, desc: &Descriptor) -> f64 {
+    let n = discretize(desc.bound, desc).max(1) as i64;
+    let i = ((x / desc.delta_x).round() as i64).rem_euclid(n) as u64;
+
+    let mut z = desc
+        .seed
+        .wrapping_add(i.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+fn boundary_value(u: &nalgebra::DVector<f64>, i: isize, desc: &Descriptor) -> f64 {
+    let n = u.len() as isize;
+
+    match desc.boundary_condition {
+        BoundaryCondition::Periodic => u[i.rem_euclid(n) as usize],
+        BoundaryCondition::ZeroGradient => u[i.clamp(0, n - 1) as usize],
+        BoundaryCondition::Dirichlet => {
+            if (0..n).contains(&i) {
+                u[i as usize]
+            } else {
+                0.0
+            }
+        }
+        BoundaryCondition::Reflective => {
+            let i = i.rem_euclid(2 * n);
+            let i = if i < n { i } else { 2 * n - 1 - i };
+            u[i as usize]
+        }
+    }
+}
+
+fn extend(u: &nalgebra::DVector<f64>, radius: usize, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len() + 2 * radius;
+    nalgebra::DVector::from_fn(n, |i, _| {
+        boundary_value(u, i as isize - radius as isize, desc)
+    })
+}
+
+fn extend_gradient(
+    g: &nalgebra::DVector<f64>,
+    radius: usize,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let n = g.len() as isize;
+    nalgebra::DVector::from_fn(g.len() + 2 * radius, |i, _| {
+        let i = i as isize - radius as isize;
+        if (0..n).contains(&i) {
+            return g[i as usize];
+        }
+
+        match desc.boundary_condition {
+            BoundaryCondition::Periodic => g[i.rem_euclid(n) as usize],
+            BoundaryCondition::ZeroGradient | BoundaryCondition::Dirichlet => 0.0,
+            BoundaryCondition::Reflective => {
+                let i = i.rem_euclid(2 * n);
+                if i < n {
+                    g[i as usize]
+                } else {
+                    -g[(2 * n - 1 - i) as usize]
+                }
+            }
+        }
+    })
+}
+
+fn gradient(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let u = extend(u, 1, desc);
+    nalgebra::DVector::from_fn(u.len() - 2, |i, _| {
+        (u[i + 2] - u[i]) / (2.0 * node_width(i + 1, u.len(), desc))
+    })
+}
+
+fn with_boundary<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    radius: usize,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let u = extend(u, radius, desc);
+    diff_fn(&u, desc).rows(radius, n).into_owned()
+}
+
+fn cell_position(i: usize, len: usize, desc: &Descriptor) -> f64 {
+    let offset = (len - discretize(desc.bound, desc)) / 2;
+    if desc.stretching == 0.0 {
+        return (i as f64 - offset as f64) * desc.delta_x;
+    }
+    node_position(i as isize - offset as isize, desc)
+}
+
+fn node_position(i: isize, desc: &Descriptor) -> f64 {
+    if desc.stretching == 0.0 {
+        return i as f64 * desc.delta_x;
+    }
+
+    let n = discretize(desc.bound, desc) as isize;
+    let length = domain_length(desc);
+    let xi = i.rem_euclid(n) as f64 / n as f64;
+    let s = desc.stretching;
+    length * (s * xi).sinh() / s.sinh() + i.div_euclid(n) as f64 * length
+}
+
+fn node_width(i: usize, len: usize, desc: &Descriptor) -> f64 {
+    if desc.stretching == 0.0 {
+        return desc.delta_x;
+    }
+
+    let offset = ((len - discretize(desc.bound, desc)) / 2) as isize;
+    let i = i as isize - offset;
+    (node_position(i + 1, desc) - node_position(i - 1, desc)) / 2.0
+}
+
+fn velocity(x: f64, desc: &Descriptor) -> f64 {
+    match desc.velocity_field {
+        VelocityField::Constant => desc.vel,
+        VelocityField::Sine => desc.vel * (desc.vel_freq * x).sin(),
+    }
+}
+
+fn velocity_gradient(x: f64, desc: &Descriptor) -> f64 {
+    match desc.velocity_field {
+        VelocityField::Constant => 0.0,
+        VelocityField::Sine => desc.vel * desc.vel_freq * (desc.vel_freq * x).cos(),
+    }
+}
+
+fn flux(u: f64, x: f64, desc: &Descriptor) -> f64 {
+    match desc.equation {
+        Equation::LinearAdvection => velocity(x, desc) * u,
+        Equation::Burgers => 0.5 * u * u,
+    }
+}
+
+fn fluxes(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    nalgebra::DVector::from_fn(u.len(), |i, _| {
+        flux(u[i], cell_position(i, u.len(), desc), desc)
+    })
+}
+
+fn advection_form(desc: &Descriptor) -> bool {
+    desc.form == Form::Advection && desc.equation == Equation::LinearAdvection
+}
+
+fn transported(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    if advection_form(desc) {
+        u.clone()
+    } else {
+        fluxes(u, desc)
+    }
+}
+
+fn form_scale(i: usize, n: usize, desc: &Descriptor) -> f64 {
+    if advection_form(desc) {
+        velocity(cell_position(i, n, desc), desc)
+    } else {
+        1.0
+    }
+}
+
+fn wave_speed(u: f64, x: f64, desc: &Descriptor) -> f64 {
+    match desc.equation {
+        Equation::LinearAdvection => velocity(x, desc),
+        Equation::Burgers => u,
+    }
+}
+
+fn forward_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let p = -desc.delta_t;
+    let f = transported(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    for i in 0..n - 1 {
+        ret[i] = (f[i + 1] - f[i]) / node_width(i, n, desc) * form_scale(i, n, desc) * p;
+    }
+
+    ret
+}
+
+fn backward_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let p = -desc.delta_t;
+    let f = transported(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    for i in 1..n {
+        ret[i] = (f[i] - f[i - 1]) / node_width(i, n, desc) * form_scale(i, n, desc) * p;
+    }
+
+    ret
+}
+
+fn central_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let p = -desc.delta_t;
+    let f = transported(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let weights: &[f64] = match desc.central_order {
+        4 => &[2.0 / 3.0, -1.0 / 12.0],
+        6 => &[3.0 / 4.0, -3.0 / 20.0, 1.0 / 60.0],
+        _ => &[1.0 / 2.0],
+    };
+
+    let r = SpatialScheme::Central.stencil_radius();
+    for i in r..u.len() - r {
+        let grad_1 = weights
+            .iter()
+            .enumerate()
+            .map(|(k, w)| w * (f[i + k + 1] - f[i - k - 1]))
+            .sum::<f64>()
+            / node_width(i, n, desc);
+        ret[i] = grad_1 * form_scale(i, n, desc) * p;
+    }
+
+    ret
+}
+
+fn upwind_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let backward = backward_diff(u, desc);
+    let forward = forward_diff(u, desc);
+
+    nalgebra::DVector::from_fn(u.len(), |i, _| {
+        if 0.0 <= wave_speed(u[i], cell_position(i, u.len(), desc), desc) {
+            backward[i]
+        } else {
+            forward[i]
+        }
+    })
+}
+
+fn lax_wendroff_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+    let f = fluxes(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let r = SpatialScheme::LaxWendroff.stencil_radius();
+    for i in r..u.len() - r {
+        let q = wave_speed(u[i], cell_position(i, n, desc), desc) * p;
+        let grad_1 = (f[i + 1] - f[i - 1]) / (2.0 * dx);
+        let grad_2 = (u[i + 1] - 2.0 * u[i] + u[i - 1]) / (2.0 * dx * dx);
+        ret[i] = grad_1 * p + grad_2 * q * q;
+    }
+
+    ret
+}
+
+fn beam_warming_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+    let f = fluxes(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let r = SpatialScheme::BeamWarming.stencil_radius();
+    for i in r..u.len() - r {
+        let a = wave_speed(u[i], cell_position(i, n, desc), desc);
+        let q = a * p;
+        let (grad_1, grad_2) = if 0.0 <= a {
+            (
+                (3.0 * f[i] - 4.0 * f[i - 1] + f[i - 2]) / (2.0 * dx),
+                (u[i] - 2.0 * u[i - 1] + u[i - 2]) / (2.0 * dx * dx),
+            )
+        } else {
+            (
+                (-3.0 * f[i] + 4.0 * f[i + 1] - f[i + 2]) / (2.0 * dx),
+                (u[i] - 2.0 * u[i + 1] + u[i + 2]) / (2.0 * dx * dx),
+            )
+        };
+        ret[i] = grad_1 * p + grad_2 * q * q;
+    }
+
+    ret
+}
+
+fn fromm_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    0.5 * (lax_wendroff_diff(u, desc) + beam_warming_diff(u, desc))
+}
+
+// A scoped spawn and join costs about 10 us per thread, while one WENO cell
+// costs about 30 ns per stage (both measured by benches/schemes.rs), so
+// a thread breaks even at roughly 350 cells. Each thread gets several times
+// that before the work is split at all, which is why the threshold grows with
+// the thread count.
+#[cfg(feature = "parallel")]
+const CELLS_PER_THREAD: usize = 2048;
+
+#[cfg(all(test, feature = "parallel"))]
+thread_local! {
+    static THREAD_COUNT: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+#[cfg(feature = "parallel")]
+fn thread_count() -> usize {
+    #[cfg(test)]
+    if let Some(threads) = THREAD_COUNT.get() {
+        return threads;
+    }
+
+    static THREADS: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
+    *THREADS.get_or_init(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+}
+
+#[cfg(feature = "parallel")]
+fn fill_cells<F: Fn(usize) -> f64 + Sync>(
+    ret: &mut nalgebra::DVector<f64>,
+    range: std::ops::Range<usize>,
+    cell_fn: F,
+) {
+    let threads = thread_count();
+    let (start, cells) = (range.start, &mut ret.as_mut_slice()[range]);
+    if threads == 1 || cells.len() < threads * CELLS_PER_THREAD {
+        fill_chunk(cells, start, &cell_fn);
+        return;
+    }
+
+    let chunk = cells.len().div_ceil(threads);
+    let cell_fn = &cell_fn;
+    std::thread::scope(|scope| {
+        for (k, cells) in cells.chunks_mut(chunk).enumerate() {
+            scope.spawn(move || fill_chunk(cells, start + k * chunk, cell_fn));
+        }
+    });
+}
+
+// Kept out of line so that the stencil closure has a single call site and
+// still inlines into the loop, as it does without the feature.
+#[cfg(feature = "parallel")]
+#[inline(never)]
+fn fill_chunk<F: Fn(usize) -> f64>(cells: &mut [f64], offset: usize, cell_fn: &F) {
+    for (j, cell) in cells.iter_mut().enumerate() {
+        *cell = cell_fn(offset + j);
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn fill_cells<F: Fn(usize) -> f64>(
+    ret: &mut nalgebra::DVector<f64>,
+    range: std::ops::Range<usize>,
+    cell_fn: F,
+) {
+    range.for_each(|i| ret[i] = cell_fn(i));
+}
+
+fn eno_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+    let f = fluxes(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let d_1h = |i: usize| (f[i + 1] - f[i]) / dx;
+    let d_2m = |i: usize| (d_1h(i) - d_1h(i - 1)) / (2.0 * dx);
+    let d_3h = |i: usize| (d_2m(i + 1) - d_2m(i)) / (3.0 * dx);
+
+    let r = SpatialScheme::ENO.stencil_radius();
+    fill_cells(&mut ret, r..n - r, |i| {
+        let b_1 = 0.0 <= wave_speed(u[i], cell_position(i, n, desc), desc);
+        let k = if b_1 { i - 1 } else { i };
+
+        let b_2 = 0.0 <= d_2m(k + 1).abs() - d_2m(k).abs();
+        let (c_2, l) = if b_2 {
+            (d_2m(k), k - 1)
+        } else {
+            (d_2m(k + 1), k)
+        };
+
+        let b_3 = 0.0 <= d_3h(l + 1).abs() - d_3h(l).abs();
+        let c_3 = if b_3 { d_3h(l) } else { d_3h(l + 1) };
+
+        let s_2 = i as f64 - k as f64;
+        let s_3 = i as f64 - l as f64;
+        let q_1 = d_1h(k);
+        let q_2 = c_2 * (2.0 * s_2 - 1.0) * dx;
+        let q_3 = c_3 * (3.0 * s_3 * s_3 - 6.0 * s_3 + 2.0) * dx * dx;
+
+        (q_1 + q_2 + q_3) * p
+    });
+
+    ret
+}
+
+fn weno_differences<F: Fn(usize) -> f64>(
+    i: usize,
+    d_1l: F,
+    u: f64,
+    n: usize,
+    desc: &Descriptor,
+) -> [f64; 5] {
+    if 0.0 <= wave_speed(u, cell_position(i, n, desc), desc) {
+        [d_1l(i - 2), d_1l(i - 1), d_1l(i), d_1l(i + 1), d_1l(i + 2)]
+    } else {
+        [d_1l(i + 3), d_1l(i + 2), d_1l(i + 1), d_1l(i), d_1l(i - 1)]
+    }
+}
+
+fn weno_stencils(d: [f64; 5]) -> ([f64; 3], [f64; 3]) {
+    let u_1 = 1.0 / 3.0 * d[0] - 7.0 / 6.0 * d[1] + 11.0 / 6.0 * d[2];
+    let u_2 = -1.0 / 6.0 * d[1] + 5.0 / 6.0 * d[2] + 1.0 / 3.0 * d[3];
+    let u_3 = 1.0 / 3.0 * d[2] + 5.0 / 6.0 * d[3] - 1.0 / 6.0 * d[4];
+
+    let s_1 = 13.0 / 12.0 * (d[0] - 2.0 * d[1] + d[2]).powi(2)
+        + 1.0 / 4.0 * (d[0] - 4.0 * d[1] + 3.0 * d[2]).powi(2);
+    let s_2 = 13.0 / 12.0 * (d[1] - 2.0 * d[2] + d[3]).powi(2) + 1.0 / 4.0 * (d[1] - d[3]).powi(2);
+    let s_3 = 13.0 / 12.0 * (d[2] - 2.0 * d[3] + d[4]).powi(2)
+        + 1.0 / 4.0 * (3.0 * d[2] - 4.0 * d[3] + d[4]).powi(2);
+
+    ([u_1, u_2, u_3], [s_1, s_2, s_3])
+}
+
+fn weno_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+    let f = fluxes(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let d_1l = |i: usize| (f[i] - f[i - 1]) / dx;
+
+    let r = SpatialScheme::WENO.stencil_radius();
+    fill_cells(&mut ret, r..n - r, |i| {
+        let d = weno_differences(i, d_1l, u[i], n, desc);
+        let ([u_1, u_2, u_3], s) = weno_stencils(d);
+        let [w_1, w_2, w_3] = weno_js_weights(s);
+
+        (w_1 * u_1 + w_2 * u_2 + w_3 * u_3) * p
+    });
+
+    ret
+}
+
+fn weno_z_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+    let f = fluxes(u, desc);
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let d_1l = |i: usize| (f[i] - f[i - 1]) / dx;
+
+    let r = SpatialScheme::WenoZ.stencil_radius();
+    fill_cells(&mut ret, r..n - r, |i| {
+        let d = weno_differences(i, d_1l, u[i], n, desc);
+        let ([u_1, u_2, u_3], s) = weno_stencils(d);
+        let [w_1, w_2, w_3] = weno_z_weights(s);
+
+        (w_1 * u_1 + w_2 * u_2 + w_3 * u_3) * p
+    });
+
+    ret
+}
+
+fn weno_js_weights([s_1, s_2, s_3]: [f64; 3]) -> [f64; 3] {
+    let a_1 = 0.1 / (s_1 + 1e-6).powi(2);
+    let a_2 = 0.6 / (s_2 + 1e-6).powi(2);
+    let a_3 = 0.3 / (s_3 + 1e-6).powi(2);
+
+    let sum = a_1 + a_2 + a_3;
+    [a_1 / sum, a_2 / sum, a_3 / sum]
+}
+
+fn weno_z_weights([s_1, s_2, s_3]: [f64; 3]) -> [f64; 3] {
+    let tau = (s_1 - s_3).abs();
+
+    let a_1 = 0.1 * (1.0 + (tau / (s_1 + 1e-40)).powi(2));
+    let a_2 = 0.6 * (1.0 + (tau / (s_2 + 1e-40)).powi(2));
+    let a_3 = 0.3 * (1.0 + (tau / (s_3 + 1e-40)).powi(2));
+
+    let sum = a_1 + a_2 + a_3;
+    [a_1 / sum, a_2 / sum, a_3 / sum]
+}
+
+fn weno_weights(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> Vec<[f64; 3]> {
+    let r = desc.spatial_scheme.stencil_radius();
+    let u = extend(u, r, desc);
+    let n = u.len();
+    let f = fluxes(&u, desc);
+
+    let d_1l = |i: usize| (f[i] - f[i - 1]) / desc.delta_x;
+    (r..n - r)
+        .map(|i| {
+            let (_, s) = weno_stencils(weno_differences(i, d_1l, u[i], n, desc));
+            match desc.spatial_scheme {
+                SpatialScheme::WenoZ => weno_z_weights(s),
+                _ => weno_js_weights(s),
+            }
+        })
+        .collect()
+}
+
+fn limiter_phi(r: f64, limiter: Limiter) -> f64 {
+    match limiter {
+        Limiter::Minmod => r.clamp(0.0, 1.0),
+        Limiter::Superbee => (2.0 * r).min(1.0).max(r.min(2.0)).max(0.0),
+        Limiter::VanLeer => (r + r.abs()) / (1.0 + r.abs()),
+        Limiter::MC => (2.0 * r).min((1.0 + r) / 2.0).clamp(0.0, 2.0),
+    }
+}
+
+fn limited_slope(d_l: f64, d_r: f64, limiter: Limiter) -> f64 {
+    if d_r == 0.0 {
+        return 0.0;
+    }
+
+    limiter_phi(d_l / d_r, limiter) * d_r
+}
+
+const LIMITER_TOLERANCE: f64 = 0.1;
+
+fn is_limited(d_l: f64, d_r: f64, limiter: Limiter) -> bool {
+    if d_r == 0.0 {
+        return d_l != 0.0;
+    }
+
+    let r = d_l / d_r;
+    (limiter_phi(r, limiter) - 0.5 * (1.0 + r)).abs() > LIMITER_TOLERANCE
+}
+
+fn limiter_activity(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> Vec<bool> {
+    let u = extend(u, 1, desc);
+    (1..u.len() - 1)
+        .map(|i| is_limited(u[i] - u[i - 1], u[i + 1] - u[i], desc.limiter))
+        .collect()
+}
+
+fn upwind_flux(u_l: f64, u_r: f64, x: f64, desc: &Descriptor) -> f64 {
+    if 0.0 <= wave_speed((u_l + u_r) / 2.0, x, desc) {
+        flux(u_l, x, desc)
+    } else {
+        flux(u_r, x, desc)
+    }
+}
+
+fn godunov_flux(u_l: f64, u_r: f64, x: f64, desc: &Descriptor) -> f64 {
+    let f_l = flux(u_l, x, desc);
+    let f_r = flux(u_r, x, desc);
+    let sonic = desc.equation == Equation::Burgers && u_l.min(u_r) < 0.0 && 0.0 < u_l.max(u_r);
+
+    if u_l <= u_r {
+        let f = f_l.min(f_r);
+        if sonic {
+            f.min(flux(0.0, x, desc))
+        } else {
+            f
+        }
+    } else {
+        f_l.max(f_r)
+    }
+}
+
+fn rusanov_flux(u_l: f64, u_r: f64, x: f64, desc: &Descriptor) -> f64 {
+    let alpha = wave_speed(u_l, x, desc)
+        .abs()
+        .max(wave_speed(u_r, x, desc).abs());
+    0.5 * (flux(u_l, x, desc) + flux(u_r, x, desc)) - 0.5 * alpha * (u_r - u_l)
+}
+
+fn finite_volume_diff<F: Fn(f64, f64, f64, &Descriptor) -> f64>(
+    u: &nalgebra::DVector<f64>,
+    face_flux: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+
+    let face_flux = |i: usize| {
+        let x = cell_position(i, n, desc) + 0.5 * dx;
+        face_flux(u[i], u[i + 1], x, desc)
+    };
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    for i in 1..n - 1 {
+        ret[i] = (face_flux(i) - face_flux(i - 1)) / dx * p;
+    }
+
+    ret
+}
+
+fn flux_corrected(
+    u: &nalgebra::DVector<f64>,
+    low: &nalgebra::DVector<f64>,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let r = desc.delta_t / desc.delta_x;
+    let u = extend(u, 2, desc);
+    let low = extend(low, 2, desc);
+    let f = fluxes(&u, desc);
+
+    let antidiffusion = nalgebra::DVector::from_fn(n + 3, |i, _| {
+        let a = 0.5
+            * (wave_speed(u[i], cell_position(i, n + 4, desc), desc)
+                + wave_speed(u[i + 1], cell_position(i + 1, n + 4, desc), desc));
+        let low_flux = if 0.0 <= a { f[i] } else { f[i + 1] };
+        let high_flux = 0.5 * (f[i] + f[i + 1]) - 0.5 * r * a * (f[i + 1] - f[i]);
+        let a = r * (high_flux - low_flux);
+        if a * (low[i + 1] - low[i]) < 0.0 {
+            0.0
+        } else {
+            a
+        }
+    });
+
+    let bounds = |i: usize| {
+        (i - 1..=i + 1)
+            .flat_map(|j| [u[j], low[j]])
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            })
+    };
+    let ratio = |q: f64, p: f64| if 0.0 < p { (q / p).min(1.0) } else { 0.0 };
+    let mut r_plus = nalgebra::DVector::zeros(n + 4);
+    let mut r_minus = nalgebra::DVector::zeros(n + 4);
+    for i in 1..n + 3 {
+        let (min, max) = bounds(i);
+        let (a_in, a_out) = (antidiffusion[i - 1], antidiffusion[i]);
+        let p_plus = a_in.max(0.0) - a_out.min(0.0);
+        let p_minus = a_out.max(0.0) - a_in.min(0.0);
+        r_plus[i] = ratio(max - low[i], p_plus);
+        r_minus[i] = ratio(low[i] - min, p_minus);
+    }
+
+    let limited = |i: usize| {
+        let a = antidiffusion[i];
+        let c = if 0.0 <= a {
+            r_plus[i + 1].min(r_minus[i])
+        } else {
+            r_plus[i].min(r_minus[i + 1])
+        };
+        c * a
+    };
+
+    nalgebra::DVector::from_fn(n, |i, _| {
+        let i = i + 2;
+        low[i] - (limited(i) - limited(i - 1))
+    })
+}
+
+fn godunov_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    finite_volume_diff(u, godunov_flux, desc)
+}
+
+fn rusanov_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    finite_volume_diff(u, rusanov_flux, desc)
+}
+
+fn muscl_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+
+    let mut slope = nalgebra::DVector::zeros(n);
+    for i in 1..n - 1 {
+        slope[i] = limited_slope(u[i] - u[i - 1], u[i + 1] - u[i], desc.limiter);
+    }
+
+    let face_flux = |i: usize| {
+        let u_l = u[i] + 0.5 * slope[i];
+        let u_r = u[i + 1] - 0.5 * slope[i + 1];
+        let x = cell_position(i, n, desc) + 0.5 * dx;
+        upwind_flux(u_l, u_r, x, desc)
+    };
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let r = SpatialScheme::MUSCL.stencil_radius();
+    for i in r..n - r {
+        ret[i] = (face_flux(i) - face_flux(i - 1)) / dx * p;
+    }
+
+    ret
+}
+
+fn flux_limited_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+
+    let face_flux = |i: usize| {
+        let x = cell_position(i, n, desc) + 0.5 * dx;
+        let a = wave_speed((u[i] + u[i + 1]) / 2.0, x, desc);
+        let d = u[i + 1] - u[i];
+        let d_upwind = if 0.0 <= a {
+            u[i] - u[i - 1]
+        } else {
+            u[i + 2] - u[i + 1]
+        };
+        let phi = if d == 0.0 {
+            0.0
+        } else {
+            limiter_phi(d_upwind / d, desc.limiter)
+        };
+
+        let c = a * desc.delta_t / dx;
+        upwind_flux(u[i], u[i + 1], x, desc) + 0.5 * a.abs() * (1.0 - c.abs()) * phi * d
+    };
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let r = SpatialScheme::FluxLimited.stencil_radius();
+    for i in r..n - r {
+        ret[i] = (face_flux(i) - face_flux(i - 1)) / dx * p;
+    }
+
+    ret
+}
+
+fn quick_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    let dx = desc.delta_x;
+    let p = -desc.delta_t;
+    let f = fluxes(u, desc);
+
+    let face_flux = |i: usize| {
+        let x = cell_position(i, n, desc) + 0.5 * dx;
+        if 0.0 <= wave_speed((u[i] + u[i + 1]) / 2.0, x, desc) {
+            (-f[i - 1] + 6.0 * f[i] + 3.0 * f[i + 1]) / 8.0
+        } else {
+            (3.0 * f[i] + 6.0 * f[i + 1] - f[i + 2]) / 8.0
+        }
+    };
+
+    let mut ret = nalgebra::DVector::zeros(n);
+
+    let r = SpatialScheme::QUICK.stencil_radius();
+    for i in r..n - r {
+        ret[i] = (face_flux(i) - face_flux(i - 1)) / dx * p;
+    }
+
+    ret
+}
+
+fn forward_euler<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    u + diff_fn(u, desc)
+}
+
+fn adams_bashforth2<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    previous_diff: &mut Option<(nalgebra::DVector<f64>, f64)>,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let k = diff_fn(u, desc);
+    let u_1 = match previous_diff.take() {
+        Some((k_0, delta_t)) => {
+            let r = desc.delta_t / delta_t;
+            u + (1.0 + 0.5 * r) * &k - 0.5 * r * r * k_0
+        }
+        None => u + &k,
+    };
+    *previous_diff = Some((k, desc.delta_t));
+    u_1
+}
+
+fn rk2<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let k_0 = diff_fn(u, desc);
+    let u_1 = u + &k_0;
+    (2.0 * u + k_0 + diff_fn(&u_1, desc)) / 2.0
+}
+
+fn rk3<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let k_0 = diff_fn(u, desc);
+    let u_1 = u + &k_0;
+    let k_1 = diff_fn(&u_1, desc);
+    let u_2 = (4.0 * u + &k_0 + &k_1) / 4.0;
+    (6.0 * u + k_0 + k_1 + 4.0 * diff_fn(&u_2, desc)) / 6.0
+}
+
+fn rk4<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let u_1 = u + diff_fn(u, desc);
+
+    let u_01 = (u + &u_1) / 2.0;
+    let u_2 = u + diff_fn(&u_01, desc);
+
+    let u_02 = (u + &u_2) / 2.0;
+    let u_3 = u + diff_fn(&u_02, desc);
+
+    let u_4 = u + diff_fn(&u_3, desc);
+
+    (&u_1 + 2.0 * &u_2 + 2.0 * &u_3 + u_4) / 6.0
+}
+
+const DORMAND_PRINCE_A: [[f64; 6]; 7] = [
+    [0.0; 6],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+        0.0,
+        0.0,
+    ],
+    [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+        0.0,
+    ],
+    [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+const DORMAND_PRINCE_ERROR: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+const MAX_REJECTIONS: usize = 50;
+
+fn dormand_prince(
+    u: &nalgebra::DVector<f64>,
+    desc: &Descriptor,
+) -> (nalgebra::DVector<f64>, f64, f64) {
+    let diff_fn = diff_operator(desc.spatial_scheme);
+    let radius = desc.spatial_scheme.stencil_radius();
+    let mut rate_desc = desc.clone();
+    rate_desc.delta_t = 1.0;
+    let rate = |u: &nalgebra::DVector<f64>| with_boundary(u, diff_fn, radius, &rate_desc);
+
+    let k_0 = rate(u);
+    let mut h = desc.delta_t;
+    let mut rejections = 0;
+    loop {
+        let mut k = vec![k_0.clone()];
+        let mut v = u.clone();
+        for a in &DORMAND_PRINCE_A[1..] {
+            let zero = nalgebra::DVector::zeros(u.len());
+            v = u + h * a.iter().zip(&k).fold(zero, |sum, (a, k)| sum + *a * k);
+            k.push(rate(&v));
+        }
+
+        let error = DORMAND_PRINCE_ERROR
+            .iter()
+            .zip(&k)
+            .fold(nalgebra::DVector::zeros(u.len()), |sum, (e, k)| {
+                sum + *e * k
+            })
+            * h;
+        let error = (0..u.len())
+            .map(|i| {
+                let scale = desc.tolerance * (1.0 + u[i].abs().max(v[i].abs()));
+                (error[i] / scale).powi(2)
+            })
+            .sum::<f64>()
+            / u.len() as f64;
+        let error = error.sqrt();
+
+        let factor = if error.is_nan() {
+            0.2
+        } else {
+            (0.9 * error.powf(-0.2)).clamp(0.2, 5.0)
+        };
+        if error <= 1.0 || MAX_REJECTIONS <= rejections {
+            return (v, h, h * factor);
+        }
+        h *= factor.min(1.0);
+        rejections += 1;
+    }
+}
+
+fn tvd_rk2<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let u_1 = u + diff_fn(u, desc);
+    (u + &u_1 + diff_fn(&u_1, desc)) / 2.0
+}
+
+fn tvd_rk3<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let u_1 = u + diff_fn(u, desc);
+    let u_2 = (3.0 * u + &u_1 + &diff_fn(&u_1, desc)) / 4.0;
+    (u + 2.0 * &u_2 + 2.0 * diff_fn(&u_2, desc)) / 3.0
+}
+
+fn tvd_rk4<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+    u: &nalgebra::DVector<f64>,
+    diff_fn: F,
+    desc: &Descriptor,
+) -> nalgebra::DVector<f64> {
+    let k_0 = diff_fn(u, desc);
+    let u_1 = (2.0 * u + &k_0) / 2.0;
+    let k_1 = diff_fn(&u_1, desc);
+    let u_2 = (2.0 * u - &k_0 + 2.0 * &u_1 + 2.0 * &k_1) / 4.0;
+    let k_2 = diff_fn(&u_2, desc);
+    let u_3 = (u - k_0 + 2.0 * &u_1 - 3.0 * &k_1 + 6.0 * &u_2 + 9.0 * k_2) / 9.0;
+    (2.0 * &u_1 + k_1 + 2.0 * &u_2 + 2.0 * &u_3 + diff_fn(&u_3, desc)) / 6.0
+}
+
+#[derive(Clone, Debug)]
+struct Tridiagonal {
+    lower: nalgebra::DVector<f64>,
+    diag: nalgebra::DVector<f64>,
+    upper: nalgebra::DVector<f64>,
+}
+
+impl Tridiagonal {
+    fn identity_plus(&self, scale: f64) -> Self {
+        Self {
+            lower: scale * &self.lower,
+            diag: self.diag.map(|d| 1.0 + scale * d),
+            upper: scale * &self.upper,
+        }
+    }
+
+    fn mul(&self, u: &nalgebra::DVector<f64>) -> nalgebra::DVector<f64> {
+        let n = u.len();
+        nalgebra::DVector::from_fn(n, |i, _| {
+            self.lower[i] * u[(i + n - 1) % n]
+                + self.diag[i] * u[i]
+                + self.upper[i] * u[(i + 1) % n]
+        })
+    }
+
+    // `lower[0]` and `upper[n - 1]` are the periodic corner entries.
+    fn solve(&self, rhs: &nalgebra::DVector<f64>) -> nalgebra::DVector<f64> {
+        let n = rhs.len();
+        let corner_top = self.lower[0];
+        let corner_bottom = self.upper[n - 1];
+
+        if corner_top == 0.0 && corner_bottom == 0.0 {
+            return solve_tridiagonal(&self.lower, &self.diag, &self.upper, rhs);
+        }
+
+        let gamma = if self.diag[0] != 0.0 {
+            -self.diag[0]
+        } else {
+            1.0
+        };
+        let mut diag = self.diag.clone();
+        diag[0] -= gamma;
+        diag[n - 1] -= corner_bottom * corner_top / gamma;
+
+        let x = solve_tridiagonal(&self.lower, &diag, &self.upper, rhs);
+
+        let mut v = nalgebra::DVector::zeros(n);
+        v[0] = gamma;
+        v[n - 1] = corner_bottom;
+        let z = solve_tridiagonal(&self.lower, &diag, &self.upper, &v);
+
+        let fact =
+            (x[0] + corner_top * x[n - 1] / gamma) / (1.0 + z[0] + corner_top * z[n - 1] / gamma);
+        x - fact * z
+    }
+}
+
+fn solve_tridiagonal(
+    lower: &nalgebra::DVector<f64>,
+    diag: &nalgebra::DVector<f64>,
+    upper: &nalgebra::DVector<f64>,
+    rhs: &nalgebra::DVector<f64>,
+) -> nalgebra::DVector<f64> {
+    let n = rhs.len();
+
+    // Gaussian elimination with partial pivoting; a row swap fills in a
+    // second superdiagonal, which `upper_2` holds.
+    let lower = lower.rows(1, n - 1).into_owned();
+    let mut diag = diag.clone();
+    let mut upper = upper.rows(0, n - 1).into_owned();
+    let mut upper_2 = nalgebra::DVector::zeros(n.saturating_sub(2));
+    let mut b = rhs.clone();
+
+    for i in 0..n - 1 {
+        if lower[i].abs() <= diag[i].abs() {
+            let fact = lower[i] / diag[i];
+            diag[i + 1] -= fact * upper[i];
+            b[i + 1] -= fact * b[i];
+        } else {
+            let fact = diag[i] / lower[i];
+            diag[i] = lower[i];
+            let temp = diag[i + 1];
+            diag[i + 1] = upper[i] - fact * temp;
+            if i + 2 < n {
+                upper_2[i] = upper[i + 1];
+                upper[i + 1] *= -fact;
+            }
+            upper[i] = temp;
+            let temp = b[i];
+            b[i] = b[i + 1];
+            b[i + 1] = temp - fact * b[i + 1];
+        }
+    }
+
+    let mut x = nalgebra::DVector::zeros(n);
+    for i in (0..n).rev() {
+        let mut sum = b[i];
+        if i + 1 < n {
+            sum -= upper[i] * x[i + 1];
+        }
+        if i + 2 < n {
+            sum -= upper_2[i] * x[i + 2];
+        }
+        x[i] = sum / diag[i];
+    }
+
+    x
+}
+
+fn linear_operator(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> Tridiagonal {
+    let n = u.len();
+    let dx = desc.delta_x;
+
+    let mut lower = nalgebra::DVector::zeros(n);
+    let mut diag = nalgebra::DVector::zeros(n);
+    let mut upper = nalgebra::DVector::zeros(n);
+
+    for i in 0..n {
+        let a = |j: usize| {
+            let j = if advection_form(desc) { i } else { j };
+            wave_speed(u[i], cell_position(j, n, desc), desc)
+        };
+        let (l, r) = ((i + n - 1) % n, (i + 1) % n);
+        match desc.spatial_scheme {
+            SpatialScheme::Central => {
+                lower[i] = a(l) / (2.0 * dx);
+                upper[i] = -a(r) / (2.0 * dx);
+            }
+            SpatialScheme::Upwind if 0.0 <= a(i) => {
+                lower[i] = a(l) / dx;
+                diag[i] = -a(i) / dx;
+            }
+            SpatialScheme::Upwind => {
+                diag[i] = a(i) / dx;
+                upper[i] = -a(r) / dx;
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    match desc.boundary_condition {
+        BoundaryCondition::Periodic => {}
+        BoundaryCondition::ZeroGradient | BoundaryCondition::Reflective => {
+            diag[0] += lower[0];
+            diag[n - 1] += upper[n - 1];
+            lower[0] = 0.0;
+            upper[n - 1] = 0.0;
+        }
+        BoundaryCondition::Dirichlet => {
+            lower[0] = 0.0;
+            upper[n - 1] = 0.0;
+        }
+    }
+
+    Tridiagonal { lower, diag, upper }
+}
+
+fn backward_euler(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let op = linear_operator(u, desc);
+    op.identity_plus(-desc.delta_t).solve(u)
+}
+
+fn crank_nicolson(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let op = linear_operator(u, desc);
+    let rhs = op.identity_plus(0.5 * desc.delta_t).mul(u);
+    op.identity_plus(-0.5 * desc.delta_t).solve(&rhs)
+}
+
+fn semi_lagrangian(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+    let n = u.len();
+    nalgebra::DVector::from_fn(n, |i, _| {
+        let x = cell_position(i, n, desc);
+        let departure = (x - wave_speed(u[i], x, desc) * desc.delta_t) / desc.delta_x;
+        cubic_interpolate(u, departure, desc)
+    })
+}
+
+fn cubic_interpolate(u: &nalgebra::DVector<f64>, x: f64, desc: &Descriptor) -> f64 {
+    let k = x.floor();
+    let s = x - k;
+    let k = k as isize;
+
+    let w = [
+        -s * (s - 1.0) * (s - 2.0) / 6.0,
+        (s + 1.0) * (s - 1.0) * (s - 2.0) / 2.0,
+        -(s + 1.0) * s * (s - 2.0) / 2.0,
+        (s + 1.0) * s * (s - 1.0) / 6.0,
+    ];
+    (0..4)
+        .map(|j| w[j] * boundary_value(u, k - 1 + j as isize, desc))
+        .sum()
+}
+
+fn cip(
+    u: &nalgebra::DVector<f64>,
+    g: &nalgebra::DVector<f64>,
+    desc: &Descriptor,
+) -> (nalgebra::DVector<f64>, nalgebra::DVector<f64>) {
+    let n = u.len();
+    let dx = desc.delta_x;
+
+    let mut ret_0 = nalgebra::DVector::zeros(n);
+    let mut ret_1 = nalgebra::DVector::zeros(n);
+    let rational = desc.spatial_scheme == SpatialScheme::RationalCIP;
+
+    let r = SpatialScheme::CIP.stencil_radius();
+    for i in r..u.len() - r {
+        let a = wave_speed(u[i], cell_position(i, n, desc), desc);
+        let p = -a * desc.delta_t;
+        let (j, d) = if 0.0 <= a { (i - 1, -dx) } else { (i + 1, dx) };
+        let s = (u[j] - u[i]) / d;
+        let beta = if rational && (s - g[i]) * (g[j] - s) > 0.0 {
+            ((s - g[i]) / (g[j] - s) - 1.0) / d
+        } else {
+            0.0
+        };
+        let a = (g[i] - s + (g[j] - s) * (1.0 + beta * d)) / d.powi(2);
+        let b = s * beta + (s - g[i]) / d - a * d;
+        let c = g[i] + u[i] * beta;
+
+        let denominator = 1.0 + beta * p;
+        ret_0[i] = (a * p.powi(3) + b * p.powi(2) + c * p + u[i]) / denominator;
+        ret_1[i] = (3.0 * a * p.powi(2) + 2.0 * b * p + c - ret_0[i] * beta) / denominator;
+    }
+
+    (ret_0, ret_1)
 }
 
-fn eno_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    let n = u.len();
-    let dx = desc.delta_x;
-    let p = -desc.vel * desc.delta_t;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut ret = nalgebra::DVector::zeros(n);
+    fn decay(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
+        -desc.delta_t * u
+    }
 
-    let d_1h = |i: usize| (u[i + 1] - u[i]) / dx;
-    let d_2m = |i: usize| (d_1h(i) - d_1h(i - 1)) / (2.0 * dx);
-    let d_3h = |i: usize| (d_2m(i + 1) - d_2m(i)) / (3.0 * dx);
+    fn decay_error<F: FnMut(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+        mut step: F,
+        steps: usize,
+    ) -> f64 {
+        let mut desc = Descriptor::new();
+        desc.delta_t = 1.0 / steps as f64;
 
-    for i in 3..u.len() - 3 {
-        let b_1 = 0.0 <= desc.vel;
-        let k = if b_1 { i - 1 } else { i };
+        let mut u = nalgebra::DVector::from_element(1, 1.0);
+        for _ in 0..steps {
+            u = step(&u, &desc);
+        }
 
-        let b_2 = 0.0 <= d_2m(k + 1).abs() - d_2m(k).abs();
-        let l = if b_2 { k - 1 } else { k };
+        (u[0] - (-1.0_f64).exp()).abs()
+    }
 
-        let b_3 = 0.0 <= d_3h(l + 1).abs() - d_3h(l).abs();
+    fn decay_order<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
+        step: F,
+    ) -> f64 {
+        (decay_error(&step, 20) / decay_error(&step, 40)).log2()
+    }
 
-        let q_1 = (u[i] - u[i - 1]) / dx;
-        let q_2 = if b_2 {
-            (u[i] - 2.0 * u[i - 1] + u[i - 2]) / (2.0 * dx)
-        } else {
-            (u[i + 1] - 2.0 * u[i] + u[i - 1]) / (2.0 * dx)
-        };
-        let q_3 = if b_2 && b_3 {
-            (u[i] - 3.0 * u[i - 1] + 3.0 * u[i - 2] - u[i - 3]) / (3.0 * dx)
-        } else if b_2 && !b_3 {
-            (u[i + 1] - 3.0 * u[i] + 3.0 * u[i - 1] - u[i - 2]) / (3.0 * dx)
-        } else if !b_2 && b_3 {
-            (u[i + 1] - 3.0 * u[i] + 3.0 * u[i - 1] - u[i - 2]) / (-6.0 * dx)
-        } else {
-            (u[i + 2] - 3.0 * u[i + 1] + 3.0 * u[i] - u[i - 1]) / (-6.0 * dx)
+    #[test]
+    fn rk3_is_third_order() {
+        let order = decay_order(|u, desc| rk3(u, decay, desc));
+        assert!((order - 3.0).abs() < 0.1, "order {}", order);
+    }
+
+    #[test]
+    fn tvd_rk3_is_third_order() {
+        let order = decay_order(|u, desc| tvd_rk3(u, decay, desc));
+        assert!((order - 3.0).abs() < 0.1, "order {}", order);
+    }
+
+    #[test]
+    fn adams_bashforth2_is_second_order() {
+        let error = |steps| {
+            let mut previous_diff = None;
+            decay_error(
+                |u, desc| adams_bashforth2(u, decay, &mut previous_diff, desc),
+                steps,
+            )
         };
+        let order = (error(20) / error(40)).log2();
+        assert!((order - 2.0).abs() < 0.1, "order {}", order);
+    }
 
-        ret[i] = (q_1 + q_2 + q_3) * p;
+    fn observed_order(
+        spatial_scheme: SpatialScheme,
+        temporal_scheme: TemporalScheme,
+        cfl: f64,
+    ) -> f64 {
+        let errors = [0.025, 0.0125].map(|delta_x| {
+            let mut desc = Descriptor::new();
+            desc.spatial_scheme = spatial_scheme;
+            desc.temporal_scheme = temporal_scheme;
+            desc.initial_condition = InitialCondition::Sine;
+            desc.bound = 2.0;
+            desc.delta_x = delta_x;
+            desc.delta_t = cfl * delta_x;
+
+            let mut scenario = Scenario::new(desc).unwrap();
+            scenario.advance_to(2.0);
+            scenario.error_norms().1
+        });
+
+        (errors[0] / errors[1]).log2()
     }
 
-    ret
-}
+    #[test]
+    fn spatial_schemes_converge_at_expected_order() {
+        let cases = [
+            (SpatialScheme::Central, TemporalScheme::Rk4, 0.5, 2.0),
+            (SpatialScheme::Upwind, TemporalScheme::Rk4, 0.5, 1.0),
+            (
+                SpatialScheme::LaxWendroff,
+                TemporalScheme::ForwardEuler,
+                0.5,
+                2.0,
+            ),
+            (
+                SpatialScheme::BeamWarming,
+                TemporalScheme::ForwardEuler,
+                0.5,
+                2.0,
+            ),
+            (SpatialScheme::Fromm, TemporalScheme::ForwardEuler, 0.5, 2.0),
+            (SpatialScheme::ENO, TemporalScheme::Rk4, 0.5, 3.0),
+            (SpatialScheme::WENO, TemporalScheme::Rk4, 0.5, 5.0),
+            (SpatialScheme::WenoZ, TemporalScheme::Rk4, 0.1, 5.0),
+            (SpatialScheme::QUICK, TemporalScheme::Rk4, 0.5, 2.0),
+            (SpatialScheme::CIP, TemporalScheme::ForwardEuler, 0.5, 3.0),
+            (
+                SpatialScheme::SemiLagrangian,
+                TemporalScheme::ForwardEuler,
+                2.5,
+                3.0,
+            ),
+        ];
 
-fn weno_diff(u: &nalgebra::DVector<f64>, desc: &Descriptor) -> nalgebra::DVector<f64> {
-    let n = u.len();
-    let dx = desc.delta_x;
-    let p = -desc.vel * desc.delta_t;
+        for (spatial_scheme, temporal_scheme, cfl, expected) in cases {
+            let order = observed_order(spatial_scheme, temporal_scheme, cfl);
+            assert!(
+                expected - 0.15 < order,
+                "{:?} order {} < {}",
+                spatial_scheme,
+                order,
+                expected
+            );
+        }
+    }
 
-    let mut ret = nalgebra::DVector::zeros(n);
+    #[test]
+    fn muscl_converges_faster_than_first_order() {
+        let order = observed_order(SpatialScheme::MUSCL, TemporalScheme::TvdRk2, 0.5);
+        assert!(1.5 < order, "order {}", order);
+    }
 
-    let d_1l = |i: usize| (u[i] - u[i - 1]) / dx;
+    #[test]
+    fn burgers_shock_moves_at_rankine_hugoniot_speed() {
+        let cases = [
+            (SpatialScheme::MUSCL, TemporalScheme::TvdRk2, 1.0),
+            (SpatialScheme::Rusanov, TemporalScheme::ForwardEuler, 1.0),
+            (SpatialScheme::MUSCL, TemporalScheme::TvdRk2, 2.0),
+        ];
 
-    for i in 3..u.len() - 3 {
-        let u_1 = 1.0 / 3.0 * d_1l(i - 2) - 7.0 / 6.0 * d_1l(i - 1) + 11.0 / 6.0 * d_1l(i);
-        let u_2 = -1.0 / 6.0 * d_1l(i - 1) + 5.0 / 6.0 * d_1l(i) + 1.0 / 3.0 * d_1l(i + 1);
-        let u_3 = 1.0 / 3.0 * d_1l(i) + 5.0 / 6.0 * d_1l(i + 1) - 1.0 / 6.0 * d_1l(i + 2);
+        for (spatial_scheme, temporal_scheme, amplitude) in cases {
+            let mut desc = Descriptor::new();
+            desc.equation = Equation::Burgers;
+            desc.spatial_scheme = spatial_scheme;
+            desc.temporal_scheme = temporal_scheme;
+            desc.amplitude = amplitude;
+            desc.delta_t = 0.01;
 
-        let s_1 = 13.0 / 12.0 * (d_1l(i - 2) - 2.0 * d_1l(i - 1) + d_1l(i)).powi(2)
-            + 1.0 / 4.0 * (d_1l(i - 2) - 4.0 * d_1l(i - 1) + 3.0 * d_1l(i)).powi(2);
-        let s_2 = 13.0 / 12.0 * (d_1l(i - 1) - 2.0 * d_1l(i) + d_1l(i + 1)).powi(2)
-            + 1.0 / 4.0 * (d_1l(i - 1) - d_1l(i + 1)).powi(2);
-        let s_3 = 13.0 / 12.0 * (d_1l(i) - 2.0 * d_1l(i + 1) + d_1l(i + 2)).powi(2)
-            + 1.0 / 4.0 * (3.0 * d_1l(i) - 4.0 * d_1l(i + 1) + d_1l(i + 2)).powi(2);
+            let mut scenario = Scenario::new(desc).unwrap();
+            scenario.advance_to(2.0);
+
+            let front = |u: &[f64]| u.iter().rposition(|&u| u > 0.5 * amplitude).unwrap();
+            let exact = scenario.exact_values(scenario.t);
+            let offset = front(scenario.values()).abs_diff(front(&exact));
+            assert!(
+                offset <= 1,
+                "{:?} shock offset {} cells",
+                spatial_scheme,
+                offset
+            );
+            assert!(scenario
+                .values()
+                .iter()
+                .all(|u| (-1e-12..=amplitude + 1e-12).contains(u)));
+        }
+    }
 
-        let a_1 = 0.1 / (s_1 + 1e-6).powi(2);
-        let a_2 = 0.6 / (s_2 + 1e-6).powi(2);
-        let a_3 = 0.3 / (s_3 + 1e-6).powi(2);
+    #[test]
+    fn flux_limited_superbee_is_monotone_and_sharper_than_lax_wendroff() {
+        let run = |spatial_scheme| {
+            let mut desc = Descriptor::new();
+            desc.spatial_scheme = spatial_scheme;
+            desc.limiter = Limiter::Superbee;
+            desc.delta_t = 0.025;
 
-        let w_1 = a_1 / (a_1 + a_2 + a_3);
-        let w_2 = a_2 / (a_1 + a_2 + a_3);
-        let w_3 = a_3 / (a_1 + a_2 + a_3);
+            let mut scenario = Scenario::new(desc).unwrap();
+            scenario.advance_to(5.0);
+            scenario
+        };
 
-        ret[i] = (w_1 * u_1 + w_2 * u_2 + w_3 * u_3) * p;
+        let flux_limited = run(SpatialScheme::FluxLimited);
+        let lax_wendroff = run(SpatialScheme::LaxWendroff);
+        assert!(flux_limited
+            .values()
+            .iter()
+            .all(|u| (-1e-12..=1.0 + 1e-12).contains(u)));
+        assert!(flux_limited.error_norms().0 < lax_wendroff.error_norms().0);
     }
 
-    ret
-}
+    #[test]
+    fn modified_wavenumber_matches_analytic_symbols() {
+        for w in modified_wavenumber(SpatialScheme::Central, 0.5, 16) {
+            assert!((w.real - w.k_dx.sin()).abs() < 1e-12);
+            assert!(w.imag.abs() < 1e-12);
+        }
+        for w in modified_wavenumber(SpatialScheme::Upwind, 0.5, 16) {
+            assert!((w.real - w.k_dx.sin()).abs() < 1e-12);
+            assert!((w.imag - (w.k_dx.cos() - 1.0)).abs() < 1e-12);
+        }
+    }
 
-fn forward_euler<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
-    u: &nalgebra::DVector<f64>,
-    diff_fn: F,
-    desc: &Descriptor,
-) -> nalgebra::DVector<f64> {
-    u + diff_fn(u, desc)
-}
+    #[test]
+    fn reflective_wall_returns_the_mirrored_wave() {
+        let mut desc = Descriptor::new();
+        desc.spatial_scheme = SpatialScheme::WENO;
+        desc.temporal_scheme = TemporalScheme::Rk3;
+        desc.boundary_condition = BoundaryCondition::Reflective;
+        desc.initial_condition = InitialCondition::Gaussian;
+        desc.delta_t = 0.02;
 
-fn rk2<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
-    u: &nalgebra::DVector<f64>,
-    diff_fn: F,
-    desc: &Descriptor,
-) -> nalgebra::DVector<f64> {
-    let u_1 = u + diff_fn(u, desc);
-    let u_2 = (2.0 * u + diff_fn(u, desc) + diff_fn(&u_1, desc)) / 2.0;
-    u_2
-}
+        let mut scenario = Scenario::new(desc).unwrap();
+        let mass = scenario.total_mass();
+        scenario.advance_to(10.0);
 
-fn rk3<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
-    u: &nalgebra::DVector<f64>,
-    diff_fn: F,
-    desc: &Descriptor,
-) -> nalgebra::DVector<f64> {
-    let u_1 = u + diff_fn(u, desc);
-    let u_2 = (4.0 * u + diff_fn(u, desc) + diff_fn(&u_1, desc)) / 4.0;
-    let u_3 =
-        (6.0 * u + diff_fn(&u_1, desc) + diff_fn(&u_1, desc) + 4.0 * diff_fn(&u_2, desc)) / 6.0;
-    u_3
-}
+        let u = scenario.values();
+        let peak = (0..u.len()).max_by(|&a, &b| u[a].total_cmp(&u[b])).unwrap();
+        let x = peak as f64 * scenario.desc.delta_x;
+        assert!((x - 6.95).abs() < 1e-9, "peak at {}", x);
+        assert!(scenario.error_norms().2 < 0.01);
+        assert!((scenario.total_mass() - mass).abs() < 1e-3);
+    }
 
-fn rk4<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
-    u: &nalgebra::DVector<f64>,
-    diff_fn: F,
-    desc: &Descriptor,
-) -> nalgebra::DVector<f64> {
-    let u_1 = u + diff_fn(u, desc);
+    #[test]
+    fn noise_is_reproducible_from_seed() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Noise;
+        desc.seed = 7;
 
-    let u_01 = (u + &u_1) / 2.0;
-    let u_2 = u + diff_fn(&u_01, desc);
+        let noise = init_wave(200, &desc);
+        assert_eq!(noise, init_wave(200, &desc));
+        assert!(noise.iter().all(|u| (0.0..1.0).contains(u)));
 
-    let u_02 = (u + &u_2) / 2.0;
-    let u_3 = u + diff_fn(&u_02, desc);
+        desc.seed = 8;
+        assert_ne!(noise, init_wave(200, &desc));
+    }
 
-    let u_4 = u + diff_fn(&u_3, desc);
+    #[test]
+    fn lax_wendroff_matches_canonical_stencil() {
+        let mut desc = Descriptor::new();
+        desc.delta_x = 0.1;
+        desc.delta_t = 0.07;
+        desc.bound = 1.0;
+        let c = desc.vel * desc.delta_t / desc.delta_x;
 
-    (&u_1 + 2.0 * &u_2 + 2.0 * &u_3 + u_4) / 6.0
-}
+        let u = nalgebra::DVector::from_fn(10, |i, _| (i as f64 * 0.7).sin());
+        let diff = lax_wendroff_diff(&u, &desc);
+        for i in 1..u.len() - 1 {
+            let expected =
+                -c / 2.0 * (u[i + 1] - u[i - 1]) + c * c / 2.0 * (u[i + 1] - 2.0 * u[i] + u[i - 1]);
+            assert!((diff[i] - expected).abs() < 1e-12);
+        }
 
-fn tvd_rk2<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
-    u: &nalgebra::DVector<f64>,
-    diff_fn: F,
-    desc: &Descriptor,
-) -> nalgebra::DVector<f64> {
-    let u_1 = u + diff_fn(u, desc);
-    let u_2 = (u + &u_1 + diff_fn(&u_1, desc)) / 2.0;
-    u_2
-}
+        let boundary = with_boundary(&u, lax_wendroff_diff, 1, &desc);
+        let n = u.len();
+        let expected =
+            -c / 2.0 * (u[0] - u[n - 2]) + c * c / 2.0 * (u[0] - 2.0 * u[n - 1] + u[n - 2]);
+        assert!((boundary[n - 1] - expected).abs() < 1e-12);
+    }
 
-fn tvd_rk3<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
-    u: &nalgebra::DVector<f64>,
-    diff_fn: F,
-    desc: &Descriptor,
-) -> nalgebra::DVector<f64> {
-    let u_1 = u + diff_fn(u, desc);
-    let u_2 = (3.0 * u + &u_1 + &diff_fn(&u_1, desc)) / 4.0;
-    let u_3 = (u + 2.0 * &u_2 + 2.0 * diff_fn(&u_2, desc)) / 3.0;
-    u_3
-}
+    #[test]
+    fn godunov_conserves_mass_through_transonic_burgers_flow() {
+        let mut desc = Descriptor::new();
+        desc.spatial_scheme = SpatialScheme::Godunov;
+        desc.equation = Equation::Burgers;
+        desc.initial_condition = InitialCondition::Sine;
+        desc.delta_t = 0.02;
 
-fn tvd_rk4<F: Fn(&nalgebra::DVector<f64>, &Descriptor) -> nalgebra::DVector<f64>>(
-    u: &nalgebra::DVector<f64>,
-    diff_fn: F,
-    desc: &Descriptor,
-) -> nalgebra::DVector<f64> {
-    let u_1 = (2.0 * u + diff_fn(u, desc)) / 2.0;
-    let u_2 = (2.0 * u - diff_fn(u, desc) + 2.0 * &u_1 + 2.0 * diff_fn(&u_1, desc)) / 4.0;
-    let u_3 = (u - diff_fn(u, desc) + 2.0 * &u_1 - 3.0 * diff_fn(&u_1, desc)
-        + 6.0 * &u_2
-        + 9.0 * diff_fn(&u_2, desc))
-        / 9.0;
-    let u_4 =
-        (2.0 * &u_1 + diff_fn(&u_1, desc) + 2.0 * &u_2 + 2.0 * &u_3 + diff_fn(&u_3, desc)) / 6.0;
-    u_4
-}
+        let mut scenario = Scenario::new(desc).unwrap();
+        let mass = scenario.total_mass();
+        scenario.advance_to(3.0);
+        assert!((scenario.total_mass() - mass).abs() < 1e-12);
+    }
 
-fn cip(
-    u: &nalgebra::DVector<f64>,
-    g: &nalgebra::DVector<f64>,
-    desc: &Descriptor,
-) -> (nalgebra::DVector<f64>, nalgebra::DVector<f64>) {
-    let n = u.len();
-    let dx = desc.delta_x;
-    let p = -desc.vel * desc.delta_t;
+    #[test]
+    fn two_gaussians_pass_through_linearly_and_merge_under_burgers() {
+        let peaks = |values: &[f64]| {
+            (1..values.len() - 1)
+                .filter(|&i| values[i] > values[i - 1] && values[i] >= values[i + 1])
+                .filter(|&i| values[i] > 0.05)
+                .count()
+        };
 
-    let mut ret_0 = nalgebra::DVector::zeros(n);
-    let mut ret_1 = nalgebra::DVector::zeros(n);
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::TwoGaussians;
+        desc.spatial_scheme = SpatialScheme::WENO;
+        desc.temporal_scheme = TemporalScheme::Rk3;
+        desc.delta_t = 0.005;
+        desc.delta_x = 0.02;
+        desc.bound = 30.0;
 
-    for i in 1..u.len() {
-        let a = (g[i] + g[i - 1]) / dx.powi(2) - 2.0 * (u[i] - u[i - 1]) / dx.powi(3);
-        let b = 3.0 * (u[i - 1] - u[i]) / dx.powi(2) + (2.0 * g[i] + g[i - 1]) / dx;
-        let c = g[i];
+        let mut scenario = Scenario::new(desc.clone()).unwrap();
+        scenario.advance_to(8.0);
+        assert_eq!(peaks(scenario.values()), 2);
+        assert!(scenario.error_norms().2 < 0.01);
 
-        ret_0[i] = a * p.powi(3) + b * p.powi(2) + c * p + u[i];
-        ret_1[i] = 3.0 * a * p.powi(2) + 2.0 * b * p + c;
+        desc.equation = Equation::Burgers;
+        desc.spatial_scheme = SpatialScheme::Rusanov;
+        desc.temporal_scheme = TemporalScheme::ForwardEuler;
+        let mut scenario = Scenario::new(desc).unwrap();
+        scenario.advance_to(20.0);
+        assert_eq!(peaks(scenario.values()), 1);
     }
 
-    (ret_0, ret_1)
+    #[test]
+    fn regrid_keeps_the_running_wave() {
+        for spatial_scheme in [SpatialScheme::WENO, SpatialScheme::CIP] {
+            let mut desc = Descriptor::new();
+            desc.initial_condition = InitialCondition::Gaussian;
+            desc.spatial_scheme = spatial_scheme;
+            desc.temporal_scheme = TemporalScheme::Rk3;
+            desc.delta_t = 0.005;
+            desc.delta_x = 0.04;
+
+            let mut scenario = Scenario::new(desc).unwrap();
+            scenario.advance_to(2.0);
+            let t = scenario.t;
+            scenario.regrid(0.02).unwrap();
+            assert_eq!(scenario.values().len(), 500);
+            assert_eq!(scenario.t, t);
+            assert!(scenario.error_norms().2 < 0.01);
+
+            scenario.advance_to(4.0);
+            assert!(scenario.error_norms().2 < 0.01);
+        }
+    }
+
+    #[test]
+    fn negative_velocity_mirrors_positive_velocity() {
+        for spatial_scheme in [
+            SpatialScheme::Upwind,
+            SpatialScheme::BeamWarming,
+            SpatialScheme::ENO,
+            SpatialScheme::WENO,
+            SpatialScheme::WenoZ,
+            SpatialScheme::CIP,
+        ] {
+            let error = |vel: f64| {
+                let mut desc = Descriptor::new();
+                desc.initial_condition = InitialCondition::Gaussian;
+                desc.spatial_scheme = spatial_scheme;
+                desc.temporal_scheme = TemporalScheme::Rk3;
+                desc.vel = vel;
+                desc.delta_t = 0.01;
+                desc.delta_x = 0.04;
+
+                let mut scenario = Scenario::new(desc).unwrap();
+                scenario.advance_to(2.0);
+                scenario.error_norms().1
+            };
+
+            let (forward, backward) = (error(1.0), error(-1.0));
+            assert!(backward < 1.2 * forward, "{:?}", spatial_scheme);
+        }
+    }
+
+    #[test]
+    fn central_conserves_energy_and_upwind_dissipates_it() {
+        let energy_ratio = |spatial_scheme: SpatialScheme| {
+            let mut desc = Descriptor::new();
+            desc.initial_condition = InitialCondition::Gaussian;
+            desc.spatial_scheme = spatial_scheme;
+            desc.temporal_scheme = TemporalScheme::Rk4;
+            desc.delta_t = 0.004;
+            desc.delta_x = 0.04;
+
+            let mut scenario = Scenario::new(desc).unwrap();
+            let energy = scenario.energy();
+            scenario.advance_to(2.0);
+            scenario.energy() / energy
+        };
+
+        assert!((energy_ratio(SpatialScheme::Central) - 1.0).abs() < 1e-4);
+        assert!(energy_ratio(SpatialScheme::Upwind) < 0.99);
+    }
+
+    #[test]
+    fn fct_is_monotone_and_sharper_than_upwind() {
+        let run = |fct: bool| {
+            let mut desc = Descriptor::new();
+            desc.spatial_scheme = SpatialScheme::Upwind;
+            desc.temporal_scheme = TemporalScheme::ForwardEuler;
+            desc.fct = fct;
+            desc.delta_t = 0.01;
+            desc.delta_x = 0.02;
+
+            let mut scenario = Scenario::new(desc).unwrap();
+            let mass = scenario.total_mass();
+            scenario.advance_to(5.0);
+            assert!((scenario.total_mass() - mass).abs() < 1e-12);
+            scenario
+        };
+
+        let fct = run(true);
+        assert!(fct
+            .values()
+            .iter()
+            .all(|u| (-1e-12..=1.0 + 1e-12).contains(u)));
+        assert!(fct.error_norms().0 < 0.5 * run(false).error_norms().0);
+    }
+
+    #[test]
+    fn stencil_radius_covers_each_operator() {
+        let schemes = <SpatialScheme as strum::IntoEnumIterator>::iter().filter(|scheme| {
+            !matches!(
+                scheme,
+                SpatialScheme::CIP | SpatialScheme::RationalCIP | SpatialScheme::SemiLagrangian
+            )
+        });
+
+        for spatial_scheme in schemes {
+            for vel in [1.0, -1.0] {
+                let mut desc = Descriptor::new();
+                desc.spatial_scheme = spatial_scheme;
+                desc.vel = vel;
+                desc.bound = 40.0 * desc.delta_x;
+
+                let u = nalgebra::DVector::from_fn(40, |i, _| (i as f64 * 0.9).sin());
+                let diff_fn = diff_operator(spatial_scheme);
+                let i = 20;
+                let r = spatial_scheme.stencil_radius();
+                for j in [i - r - 1, i + r + 1] {
+                    let mut perturbed = u.clone();
+                    perturbed[j] += 1.0;
+                    assert_eq!(
+                        diff_fn(&perturbed, &desc)[i],
+                        diff_fn(&u, &desc)[i],
+                        "{:?} depends on offset {}",
+                        spatial_scheme,
+                        j as isize - i as isize
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn central_stencils_reach_their_order() {
+        for central_order in [2, 4, 6] {
+            let error = |cells: usize| {
+                let mut desc = Descriptor::new();
+                desc.spatial_scheme = SpatialScheme::Central;
+                desc.central_order = central_order;
+                desc.delta_x = desc.bound / cells as f64;
+
+                let k = std::f64::consts::TAU / desc.bound;
+                let x = |i: usize| i as f64 * desc.delta_x;
+                let u = nalgebra::DVector::from_fn(cells, |i, _| (k * x(i)).sin());
+                let diff = with_boundary(&u, central_diff, 3, &desc) / -desc.delta_t;
+                (0..cells)
+                    .map(|i| (diff[i] - desc.vel * k * (k * x(i)).cos()).abs())
+                    .fold(0.0, f64::max)
+            };
+
+            let order = (error(20) / error(40)).log2();
+            assert!(
+                (order - central_order as f64).abs() < 0.1,
+                "order {} measured {}",
+                central_order,
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn stages_match_diff_fn_evaluations() {
+        let desc = Descriptor::new();
+        let u = init_wave(discretize(desc.bound, &desc), &desc);
+        for temporal_scheme in <TemporalScheme as strum::IntoEnumIterator>::iter() {
+            let forward_fn = match temporal_scheme {
+                TemporalScheme::ForwardEuler => forward_euler,
+                TemporalScheme::Rk2 => rk2,
+                TemporalScheme::Rk3 => rk3,
+                TemporalScheme::Rk4 => rk4,
+                TemporalScheme::TvdRk2 => tvd_rk2,
+                TemporalScheme::TvdRk3 => tvd_rk3,
+                TemporalScheme::TvdRk4 => tvd_rk4,
+                _ => continue,
+            };
+            let calls = std::cell::Cell::new(0);
+            let diff_fn = |u: &nalgebra::DVector<f64>, desc: &Descriptor| {
+                calls.set(calls.get() + 1);
+                with_boundary(u, upwind_diff, 1, desc)
+            };
+            forward_fn(&u, diff_fn, &desc);
+            assert_eq!(
+                calls.get(),
+                temporal_scheme.stages(),
+                "{:?}",
+                temporal_scheme
+            );
+        }
+    }
+
+    #[test]
+    fn clamp_bounds_keep_central_within_range() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Square;
+        desc.spatial_scheme = SpatialScheme::Central;
+        desc.temporal_scheme = TemporalScheme::Rk3;
+
+        let extrema = |desc: &Descriptor| {
+            let mut scenario = Scenario::new(desc.clone()).unwrap();
+            for _ in 0..60 {
+                scenario.forward();
+            }
+            let min = scenario
+                .values()
+                .iter()
+                .copied()
+                .fold(f64::INFINITY, f64::min);
+            let max = scenario
+                .values()
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            (min, max)
+        };
+
+        let (min, max) = extrema(&desc);
+        assert!(min < -0.05 && 1.05 < max, "min {} max {}", min, max);
+
+        desc.clamp_bounds = Some((0.0, 1.0));
+        let (min, max) = extrema(&desc);
+        assert!(0.0 <= min && max <= 1.0, "min {} max {}", min, max);
+    }
+
+    #[test]
+    fn rational_cip_suppresses_cip_overshoot() {
+        let extrema = |spatial_scheme| {
+            let mut desc = Descriptor::new();
+            desc.initial_condition = InitialCondition::Square;
+            desc.spatial_scheme = spatial_scheme;
+            desc.t_final = Some(5.0);
+            let mut scenario = Scenario::new(desc).unwrap();
+            while !scenario.finished() {
+                scenario.forward();
+            }
+            let min = scenario
+                .values()
+                .iter()
+                .copied()
+                .fold(f64::INFINITY, f64::min);
+            let max = scenario
+                .values()
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            (min, max)
+        };
+
+        let (min, max) = extrema(SpatialScheme::CIP);
+        assert!(min < -0.01 && 1.01 < max, "min {} max {}", min, max);
+        let (min, max) = extrema(SpatialScheme::RationalCIP);
+        assert!(-1e-3 < min && max < 1.0 + 1e-3, "min {} max {}", min, max);
+    }
+
+    #[test]
+    fn characteristics_match_closed_form_for_sine_velocity() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Gaussian;
+        desc.velocity_field = VelocityField::Sine;
+        desc.spatial_scheme = SpatialScheme::SemiLagrangian;
+
+        let (k, t) = (desc.vel_freq, 3.0);
+        for i in 0..discretize(desc.bound, &desc) {
+            let x = i as f64 * desc.delta_x;
+            let x_0 = 2.0 / k * ((0.5 * k * x).tan() * (-desc.vel * k * t).exp()).atan();
+            let expected = initial_value(x_0.rem_euclid(domain_length(&desc)), &desc);
+            let error = (characteristic_value(x, t, &desc) - expected).abs();
+            assert!(error < 1e-5, "x {} error {}", x, error);
+        }
+    }
+
+    #[test]
+    fn weno_weights_drop_the_stencil_across_a_jump() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Gaussian;
+        desc.spatial_scheme = SpatialScheme::WENO;
+        let u = init_wave(discretize(desc.bound, &desc), &desc);
+        let smooth = weno_weights(&u, &desc)[discretize(desc.x_1, &desc)];
+        for (w, ideal) in smooth.iter().zip([0.1, 0.6, 0.3]) {
+            assert!((w - ideal).abs() < 0.05, "weights {:?}", smooth);
+        }
+
+        desc.initial_condition = InitialCondition::Square;
+        let u = init_wave(discretize(desc.bound, &desc), &desc);
+        let weights = weno_weights(&u, &desc);
+        let jump = discretize(desc.x_1, &desc);
+        let collapsed = (jump - 2..jump + 3).any(|i| weights[i].iter().any(|w| *w < 1e-3));
+        assert!(collapsed, "weights {:?}", &weights[jump - 2..jump + 3]);
+    }
+
+    #[test]
+    fn reversal_retraces_only_without_numerical_diffusion() {
+        let returned_error = |spatial_scheme| {
+            let mut desc = Descriptor::new();
+            desc.initial_condition = InitialCondition::Gaussian;
+            desc.spatial_scheme = spatial_scheme;
+            desc.temporal_scheme = TemporalScheme::Rk4;
+            let mut scenario = Scenario::new(desc).unwrap();
+            scenario.advance_to(2.0);
+            scenario.set_reversed(true).unwrap();
+            while !scenario.finished() {
+                scenario.forward();
+            }
+            assert!(scenario.t.abs() < scenario.dt, "t {}", scenario.t);
+            scenario.error_norms().0
+        };
+
+        let central = returned_error(SpatialScheme::Central);
+        let upwind = returned_error(SpatialScheme::Upwind);
+        assert!(
+            central < 1e-3 && 10.0 * central < upwind,
+            "{} {}",
+            central,
+            upwind
+        );
+    }
+
+    #[test]
+    fn diagnostic_log_samples_every_stride() {
+        let mut desc = Descriptor::new();
+        desc.diagnostic_stride = 3;
+        let mut scenario = Scenario::new(desc).unwrap();
+        for _ in 0..9 {
+            scenario.forward();
+        }
+        assert_eq!(scenario.diagnostics.len(), 4);
+
+        let json = scenario.diagnostics.to_json();
+        assert!(json.starts_with("[\n  {\"t\": 0, \"mass\": "), "{}", json);
+        assert_eq!(json.matches("\"l2_error\"").count(), 4);
+
+        scenario.reset();
+        assert_eq!(scenario.diagnostics.len(), 1);
+
+        let mut desc = Descriptor::new();
+        desc.velocity_field = VelocityField::Sine;
+        desc.diagnostic_stride = 3;
+        let mut scenario = Scenario::new(desc).unwrap();
+        for _ in 0..4 {
+            scenario.forward();
+        }
+        let json = scenario.diagnostics.to_json();
+        assert!(!json.contains("null"), "{}", json);
+        assert_eq!(scenario.errors().t, 3.0 * scenario.desc.delta_t);
+        assert!(scenario.errors().l_2.is_finite());
+    }
+
+    #[test]
+    fn quadrature_l2_resolves_jumps_and_keeps_smooth_rates() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Square;
+        desc.x_1 = 2.02;
+        let scenario = Scenario::new(desc.clone()).unwrap();
+        assert_eq!(scenario.error_norms().1, 0.0);
+        assert!(0.01 < scenario.l2_error_quad());
+
+        desc.initial_condition = InitialCondition::Sine;
+        desc.spatial_scheme = SpatialScheme::CIP;
+        desc.delta_x = 0.1;
+        desc.delta_t = 0.02;
+        let rows = convergence_study(&desc, 1.0, 3, &Progress::default()).unwrap();
+        let order = rows.last().and_then(|row| row.order).unwrap();
+        assert!((order - 3.0).abs() < 0.1, "order {}", order);
+    }
+
+    #[test]
+    fn break_on_shock_fires_near_the_breaking_time() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Sine;
+        desc.equation = Equation::Burgers;
+        desc.spatial_scheme = SpatialScheme::WENO;
+        desc.temporal_scheme = TemporalScheme::Rk3;
+        desc.delta_t = 0.01;
+        desc.break_on_shock = true;
+
+        let mut scenario = Scenario::new(desc).unwrap();
+        while scenario.shock.is_none() && scenario.t < 3.0 {
+            scenario.forward();
+        }
+        assert!(scenario.shock_formed());
+
+        let breaking_time = domain_length(&scenario.desc) / (2.0 * std::f64::consts::PI);
+        let t = scenario.shock.unwrap();
+        assert!(
+            (t - breaking_time).abs() < 0.2,
+            "shock at {} for {}",
+            t,
+            breaking_time
+        );
+    }
+
+    #[test]
+    fn stretched_grid_conserves_mass_and_tracks_exact_solution() {
+        let run = |stretching: f64| {
+            let mut desc = Descriptor::new();
+            desc.initial_condition = InitialCondition::Gaussian;
+            desc.spatial_scheme = SpatialScheme::Central;
+            desc.temporal_scheme = TemporalScheme::Rk3;
+            desc.adaptive = true;
+            desc.target_cfl = 0.5;
+            desc.stretching = stretching;
+
+            let mut scenario = Scenario::new(desc).unwrap();
+            let mass = scenario.total_mass();
+            while scenario.t < 5.0 {
+                scenario.forward();
+            }
+            assert!(scenario.diverged.is_none());
+            assert!((scenario.total_mass() - mass).abs() < 1e-12);
+            scenario
+        };
+
+        let uniform = run(0.0);
+        let stretched = run(2.0);
+
+        let n = discretize(stretched.desc.bound, &stretched.desc) as isize;
+        let nodes = (0..=n)
+            .map(|i| node_position(i, &stretched.desc))
+            .collect::<Vec<_>>();
+        assert!(nodes.windows(2).all(|w| w[1] > w[0]));
+        assert!((nodes[n as usize] - domain_length(&stretched.desc)).abs() < 1e-12);
+        assert!(stretched.desc.min_spacing() < 0.6 * stretched.desc.delta_x);
+
+        assert!(stretched.error_norms().1 < 2.0 * uniform.error_norms().1);
+    }
+
+    #[test]
+    fn coarse_grids_are_rejected_before_the_stencil_underflows() {
+        for spatial_scheme in <SpatialScheme as strum::IntoEnumIterator>::iter() {
+            for n in 0..=2 * spatial_scheme.stencil_radius() + 2 {
+                let mut desc = Descriptor::new();
+                desc.spatial_scheme = spatial_scheme;
+                desc.bound = n as f64 * desc.delta_x;
+
+                match Scenario::new(desc) {
+                    Ok(mut scenario) => {
+                        assert!(n > 2 * spatial_scheme.stencil_radius());
+                        scenario.forward();
+                    }
+                    Err(e) => assert!(e.contains("cells"), "{:?}: {}", spatial_scheme, e),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn expression_initial_condition_matches_builtin_shapes() {
+        use crate::expression::Expression;
+
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Gaussian;
+        desc.amplitude = 2.0;
+        desc.sigma = 0.5;
+        desc.x_1 = 3.0;
+        desc.x_2 = 4.0;
+        let builtin = Scenario::new(desc.clone()).unwrap();
+
+        desc.initial_condition = InitialCondition::Expression;
+        desc.expression = Expression::new("2 * exp(-(x - 3.5)^2 / (2 * 0.5^2))");
+        let parsed = Scenario::new(desc.clone()).unwrap();
+        for (a, b) in builtin.values().iter().zip(parsed.values()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        assert_eq!(Expression::new("-2^2 + 3*4/2 - (1 - x)").eval(1.0), 2.0);
+        assert_eq!(Expression::new("sin(pi / 2) + ln(e)").eval(0.0), 2.0);
+
+        for source in ["exp(x", "2 +", "foo(x)", "x y", ""] {
+            desc.expression = Expression::new(source);
+            let e = Scenario::new(desc.clone()).unwrap_err();
+            assert!(e.starts_with("Invalid expression"), "{}: {}", source, e);
+        }
+
+        desc.expression = Expression::new("1 / x");
+        assert!(Scenario::new(desc).unwrap_err().contains("not finite"));
+    }
+
+    #[test]
+    fn only_the_conservation_form_preserves_mass() {
+        let run = |velocity_field: VelocityField, form: Form| {
+            let mut desc = Descriptor::new();
+            desc.initial_condition = InitialCondition::Gaussian;
+            desc.velocity_field = velocity_field;
+            desc.spatial_scheme = SpatialScheme::Central;
+            desc.temporal_scheme = TemporalScheme::Rk3;
+            desc.delta_t = 0.01;
+            desc.form = form;
+
+            let mut scenario = Scenario::new(desc).unwrap();
+            let mass = scenario.total_mass();
+            while scenario.t < 3.0 {
+                scenario.forward();
+            }
+            (scenario.total_mass() - mass, scenario)
+        };
+
+        let (drift, _) = run(VelocityField::Sine, Form::Conservation);
+        assert!(drift.abs() < 1e-12, "conservation drift {}", drift);
+        let (drift, scenario) = run(VelocityField::Sine, Form::Advection);
+        assert!(drift.abs() > 0.1, "advection drift {}", drift);
+        assert!(scenario.error_norms().1 < 0.1);
+
+        let (_, conservation) = run(VelocityField::Constant, Form::Conservation);
+        let (_, advection) = run(VelocityField::Constant, Form::Advection);
+        assert_eq!(conservation.values(), advection.values());
+    }
+
+    #[test]
+    fn rk45_grows_the_step_until_the_error_meets_the_tolerance() {
+        let run = |tolerance: f64| {
+            let mut desc = Descriptor::new();
+            desc.initial_condition = InitialCondition::Gaussian;
+            desc.spatial_scheme = SpatialScheme::WENO;
+            desc.temporal_scheme = TemporalScheme::Rk45;
+            desc.tolerance = tolerance;
+            desc.delta_t = 0.001;
+
+            let mut scenario = Scenario::new(desc).unwrap();
+            let (mut t, mut steps, mut max_dt) = (0.0, 0, 0.0_f64);
+            while scenario.t < 2.0 {
+                let dt = scenario.forward();
+                t += dt;
+                steps += 1;
+                max_dt = max_dt.max(dt);
+            }
+            assert_eq!(scenario.t, t);
+            assert!(20.0 * scenario.desc.delta_t < max_dt);
+            (steps, scenario.error_norms().1)
+        };
+
+        let (loose_steps, loose_error) = run(1e-2);
+        let (tight_steps, tight_error) = run(1e-8);
+        assert!(loose_steps < tight_steps);
+        assert!(tight_error < 1e-3, "error {}", tight_error);
+        assert!(10.0 * tight_error < loose_error);
+    }
+
+    #[test]
+    fn non_positive_delta_t_is_rejected() {
+        for delta_t in [0.0, -0.01, f64::NAN, f64::INFINITY] {
+            let mut desc = Descriptor::new();
+            desc.delta_t = delta_t;
+            desc.t_final = Some(1.0);
+            let e = Scenario::new(desc).unwrap_err();
+            assert_eq!(e, "Delta Time must be positive", "{}", delta_t);
+        }
+    }
+
+    #[test]
+    fn comparisons_follow_the_adaptive_primary_in_lockstep() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Gaussian;
+        desc.spatial_scheme = SpatialScheme::WENO;
+        desc.temporal_scheme = TemporalScheme::Rk3;
+        desc.adaptive = true;
+
+        let mut scenarios = vec![Scenario::new(desc.clone()).unwrap()];
+        desc.spatial_scheme = SpatialScheme::Upwind;
+        desc.target_cfl = 0.9;
+        scenarios.push(Scenario::new(desc.clone()).unwrap());
+        desc.spatial_scheme = SpatialScheme::Central;
+        desc.temporal_scheme = TemporalScheme::Rk45;
+        desc.adaptive = false;
+        scenarios.push(Scenario::new(desc).unwrap());
+
+        for _ in 0..50 {
+            forward_all(&mut scenarios).unwrap();
+        }
+        assert!(0.0 < scenarios[0].t);
+        for scenario in &scenarios {
+            assert_eq!(scenario.t, scenarios[0].t);
+            assert_eq!(scenario.dt, scenarios[0].dt);
+            assert!(scenario.diverged.is_none());
+        }
+    }
+
+    #[test]
+    fn comparison_added_mid_run_joins_at_the_adaptive_primary_time() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Gaussian;
+        desc.spatial_scheme = SpatialScheme::WENO;
+        desc.temporal_scheme = TemporalScheme::Rk3;
+        desc.adaptive = true;
+
+        let mut scenarios = vec![Scenario::new(desc.clone()).unwrap()];
+        for _ in 0..37 {
+            forward_all(&mut scenarios).unwrap();
+        }
+
+        desc.spatial_scheme = SpatialScheme::Upwind;
+        desc.target_cfl = 0.3;
+        let mut comparison = Scenario::new(desc).unwrap();
+        comparison.advance_to(scenarios[0].t);
+        assert_eq!(comparison.t, scenarios[0].t);
+        scenarios.push(comparison);
+
+        for _ in 0..10 {
+            forward_all(&mut scenarios).unwrap();
+        }
+        assert_eq!(scenarios[1].t, scenarios[0].t);
+    }
+
+    #[test]
+    fn rk45_ends_exactly_at_the_final_time() {
+        let mut desc = Descriptor::new();
+        desc.initial_condition = InitialCondition::Gaussian;
+        desc.spatial_scheme = SpatialScheme::WENO;
+        desc.temporal_scheme = TemporalScheme::Rk45;
+        desc.tolerance = 1e-4;
+        desc.t_final = Some(1.3);
+
+        let mut scenario = Scenario::new(desc).unwrap();
+        while !scenario.finished() {
+            scenario.forward();
+        }
+        assert!((scenario.t - 1.3).abs() < 1e-12, "t {}", scenario.t);
+
+        scenario.set_reversed(true).unwrap();
+        while !scenario.finished() {
+            scenario.forward();
+        }
+        assert!(scenario.t.abs() < 1e-12, "t {}", scenario.t);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_cells_match_the_serial_step() {
+        let mut desc = Descriptor::new();
+        desc.delta_x = MIN_DELTA_X;
+        desc.delta_t = 0.4 * desc.delta_x;
+        desc.temporal_scheme = TemporalScheme::TvdRk3;
+
+        for spatial_scheme in [
+            SpatialScheme::ENO,
+            SpatialScheme::WENO,
+            SpatialScheme::WenoZ,
+        ] {
+            desc.spatial_scheme = spatial_scheme;
+            let step = |threads: usize| {
+                THREAD_COUNT.set(Some(threads));
+                let mut scenario = Scenario::new(desc.clone()).unwrap();
+                scenario.forward();
+                THREAD_COUNT.set(None);
+                scenario.values().to_vec()
+            };
+            assert!(4 * CELLS_PER_THREAD < discretize(desc.bound, &desc));
+            assert_eq!(step(1), step(4), "{:?}", spatial_scheme);
+        }
+    }
+
+    #[test]
+    fn implicit_schemes_require_a_linear_operator() {
+        for temporal_scheme in [TemporalScheme::BackwardEuler, TemporalScheme::CrankNicolson] {
+            for spatial_scheme in <SpatialScheme as strum::IntoEnumIterator>::iter() {
+                let mut desc = Descriptor::new();
+                desc.spatial_scheme = spatial_scheme;
+                desc.temporal_scheme = temporal_scheme;
+
+                let result = validate(&desc);
+                if matches!(
+                    spatial_scheme,
+                    SpatialScheme::Central | SpatialScheme::Upwind
+                ) {
+                    assert_eq!(result, Ok(()), "{:?}", spatial_scheme);
+                } else {
+                    assert_eq!(
+                        result,
+                        Err(format!(
+                            "{:?} requires the Central or Upwind scheme",
+                            temporal_scheme
+                        )),
+                        "{:?}",
+                        spatial_scheme
+                    );
+                }
+            }
+        }
+    }
 }